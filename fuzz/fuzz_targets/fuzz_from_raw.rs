@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use vec_2_10_10_10::Vector;
+
+fuzz_target!(|data: u32| {
+    assert!(Vector::from_raw(data).check_invariants());
+});
@@ -32,13 +32,97 @@
 //!     a > b - DELTA && a < b + DELTA
 //! }
 //! ```
+//!
+//! ## Features
+//!
+//! - `rayon` enables `encode_slice_par`, which packs large slices in parallel.
+//! - `cgmath` enables conversions between `Vector` and `cgmath::Vector4<f32>`.
+//!
+//! ## Minimum Rust version
+//!
+//! Requires Rust 1.82 or newer, because the `vec_2_10_10_10!` macro uses float
+//! arithmetic in a `const fn`.
 
+#[cfg(feature = "cgmath")]
+extern crate cgmath;
+#[cfg(feature = "rayon")]
+extern crate rayon;
+
+use std::cmp;
+use std::collections::HashSet;
+use std::convert::TryFrom;
+use std::error;
 use std::fmt;
+use std::io;
+use std::mem;
+use std::num;
+use std::ops;
+use std::str;
+
+/// Creates a `Vector` at compile time.
+///
+/// Takes the same `x`, `y`, `z` and `w` arguments as `Vector::new` and packs them the same
+/// way, but can be used to define constants. Values outside `0.0..=1.0` are clamped.
+///
+/// ```
+/// #[macro_use]
+/// extern crate vec_2_10_10_10;
+///
+/// use vec_2_10_10_10::Vector;
+///
+/// const RED: Vector = vec_2_10_10_10!(1.0, 0.0, 0.0, 1.0);
+///
+/// fn main() {
+///     assert_eq!(RED.raw_value(), Vector::new(1.0, 0.0, 0.0, 1.0).raw_value());
+/// }
+/// ```
+#[macro_export]
+macro_rules! vec_2_10_10_10 {
+    ($x:expr, $y:expr, $z:expr, $w:expr) => {
+        $crate::Vector::from_raw($crate::pack4::<10, 10, 10, 2>($x, $y, $z, $w))
+    };
+}
+
+/// Packs four `0.0..=1.0` values into a `u32` with the given bit widths.
+///
+/// Every value is quantized like in `quantize`, and the fields are laid out from the least
+/// significant bit: `x` first, then `y`, `z` and `w` in the most significant bits.
+/// `Vector` uses `pack4::<10, 10, 10, 2>`, and for example `pack4::<8, 8, 8, 8>`
+/// produces little-endian RGBA8.
+///
+/// The bit widths must be non-zero and add up to at most 32, which is checked at compile time.
+///
+/// ```
+/// use vec_2_10_10_10::{pack4, Vector};
+///
+/// assert_eq!(pack4::<10, 10, 10, 2>(0.2, 0.4, 0.6, 1.0), Vector::new(0.2, 0.4, 0.6, 1.0).raw_value());
+/// assert_eq!(pack4::<8, 8, 8, 8>(1.0, 0.0, 0.5, 1.0), 0xff_80_00_ff);
+/// assert_eq!(pack4::<1, 1, 1, 29>(0.0, 0.0, 0.0, 1.0), 0xffff_fff8);
+/// assert_eq!(pack4::<29, 1, 1, 1>(1.0, 0.0, 0.0, 0.0), 0x1fff_ffff);
+/// ```
+pub const fn pack4<const XB: u8, const YB: u8, const ZB: u8, const WB: u8>(x: f32, y: f32, z: f32, w: f32) -> u32 {
+    const {
+        assert!(
+            XB > 0 && YB > 0 && ZB > 0 && WB > 0
+                && XB as u32 + YB as u32 + ZB as u32 + WB as u32 <= 32,
+            "bit widths must be non-zero and add up to at most 32"
+        );
+    }
+
+    const fn max(bits: u8) -> u32 {
+        ((1u64 << bits) - 1) as u32
+    }
+
+    quantize_to(w, max(WB)) << (XB + YB + ZB)
+        | quantize_to(z, max(ZB)) << (XB + YB)
+        | quantize_to(y, max(YB)) << XB
+        | quantize_to(x, max(XB))
+}
 
 /// Four dimensional 2-10-10-10 vector.
 ///
 /// The binary data is mapped into floating point values from `0.0` to `1.0`.
-/// The values outside this range are clamped.
+/// The values outside this range are clamped (`Vector::new` also checks the range in debug builds).
 ///
 /// The `w` dimension takes 2 bits, and can have values `0.0`, `0.3(3)`, `0.6(6)` and `1.0`.
 /// The `x`, `y` and `z` dimensions take 10 bits, each.
@@ -51,8 +135,42 @@ pub struct Vector {
     data: u32,
 }
 
+// Buffer strides and GPU uploads rely on this exact layout.
+const _: () = assert!(mem::size_of::<Vector>() == Vector::SIZE && mem::align_of::<Vector>() == 1);
+
 impl Vector {
 
+    /// Size of `Vector` in bytes.
+    ///
+    /// ```
+    /// assert_eq!(std::mem::size_of::<vec_2_10_10_10::Vector>(), vec_2_10_10_10::Vector::SIZE);
+    /// assert_eq!(std::mem::align_of::<vec_2_10_10_10::Vector>(), 1);
+    /// ```
+    pub const SIZE: usize = 4;
+
+    /// Opaque white: all values are `1.0`.
+    pub const OPAQUE_WHITE: Vector = Vector::from_raw(0xffff_ffff);
+
+    /// Transparent black: all values are `0.0`.
+    pub const TRANSPARENT_BLACK: Vector = Vector::from_raw(0);
+
+    /// All four values the 2-bit `w` dimension can decode to.
+    pub const W_VALUES: [f32; 4] = [0.0, 1.0 / 3.0, 2.0 / 3.0, 1.0];
+
+    /// Returns the value from `W_VALUES` that `w` would be stored as.
+    ///
+    /// ```
+    /// use vec_2_10_10_10::Vector;
+    ///
+    /// assert_eq!(Vector::snap_w(0.2), Vector::W_VALUES[1]);
+    /// assert_eq!(Vector::snap_w(0.9), Vector::W_VALUES[3]);
+    /// assert_eq!(Vector::snap_w(-1.0), Vector::W_VALUES[0]);
+    /// assert_eq!(Vector::snap_w(0.2), Vector::new(0.0, 0.0, 0.0, 0.2).w());
+    /// ```
+    pub fn snap_w(value: f32) -> f32 {
+        Vector::W_VALUES[quantize(value, 2) as usize]
+    }
+
     /// Creates a new Vector.
     ///
     /// First `x`, `y`, `z` values are stored in 10-bits, each.
@@ -77,29 +195,180 @@ impl Vector {
     ///     a > b - DELTA && a < b + DELTA
     /// }
     /// ```
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if any value is NaN or is outside `0.0..=1.0` by more
+    /// than `0.001`. This catches inputs in the wrong units, like `0..255`.
+    /// In release builds the check is compiled out and values are clamped instead.
     pub fn new(x: f32, y: f32, z: f32, w: f32) -> Vector {
-        let x = (clamp(x) * 1023f32).round() as u32;
-        let y = (clamp(y) * 1023f32).round() as u32;
-        let z = (clamp(z) * 1023f32).round() as u32;
-        let w = (clamp(w) * 3f32).round() as u32;
+        debug_assert!(
+            in_range(x) && in_range(y) && in_range(z) && in_range(w),
+            "vector values must be in 0.0..=1.0 range, got ({}, {}, {}, {})", x, y, z, w
+        );
+        Vector::new_clamped(x, y, z, w)
+    }
+
+    /// Returns the representable vector nearest to given values.
+    ///
+    /// This packs values the same way as `Vector::new`, but accepts any input:
+    /// values outside `0.0..=1.0` snap to the nearest end of the range, and are never
+    /// reported by the debug range check.
+    ///
+    /// ```
+    /// let value = vec_2_10_10_10::Vector::closest(0.35, 2.0, -1.0, 0.4);
+    ///
+    /// assert!(approx_equal(value.x(), 0.35));
+    /// assert_eq!(value.y(), 1.0);
+    /// assert_eq!(value.z(), 0.0);
+    /// assert!(approx_equal(value.w(), 0.333));
+    /// #
+    /// # fn approx_equal(a: f32, b: f32) -> bool {
+    /// #     const DELTA: f32 = 0.001;
+    /// #     a > b - DELTA && a < b + DELTA
+    /// # }
+    /// ```
+    pub fn closest(x: f32, y: f32, z: f32, w: f32) -> Vector {
+        Vector::new_clamped(x, y, z, w)
+    }
+
+    /// Same as `closest`, for values in `[x, y, z, w]` array.
+    pub fn closest_array(values: &[f32; 4]) -> Vector {
+        Vector::new_clamped(values[0], values[1], values[2], values[3])
+    }
+
+    /// Creates a new Vector, clamping only the values selected by `clamp_mask`.
+    ///
+    /// The mask is in `[x, y, z, w]` order. Values with `true` in the mask are clamped
+    /// to `0.0..=1.0` like in `Vector::new`. Values with `false` are stored without any
+    /// range check, and must already be in `0.0..=1.0`: out-of-range values spill into
+    /// neighbouring fields and produce garbage.
+    ///
+    /// ```
+    /// let value = vec_2_10_10_10::Vector::new_selective_clamp(0.2, 0.4, 0.6, 5.0, [false, false, false, true]);
+    ///
+    /// assert_eq!(value.raw_value(), vec_2_10_10_10::Vector::new(0.2, 0.4, 0.6, 1.0).raw_value());
+    /// ```
+    pub fn new_selective_clamp(x: f32, y: f32, z: f32, w: f32, clamp_mask: [bool; 4]) -> Vector {
+        fn field(value: f32, max: f32, clamp: bool) -> u32 {
+            if clamp {
+                quantize_to(value, max as u32)
+            } else {
+                (value * max).round() as u32
+            }
+        }
 
         let mut c: u32 = 0;
-        c |= w << 30;
-        c |= z << 20;
-        c |= y << 10;
-        c |= x << 0;
+        c |= field(w, 3f32, clamp_mask[3]) << 30;
+        c |= field(z, 1023f32, clamp_mask[2]) << 20;
+        c |= field(y, 1023f32, clamp_mask[1]) << 10;
+        c |= field(x, 1023f32, clamp_mask[0]);
 
         Vector {
             data: c
         }
     }
 
+    /// Creates a new Vector, converting values like GPUs that add `0.5` and truncate.
+    ///
+    /// Every value is clamped to `0.0..=1.0`, multiplied by `2^bits - 1`, `0.5` is added,
+    /// and the result is truncated to an integer, all in `f32` arithmetic. This is a
+    /// common hardware implementation of the unsigned normalized conversion.
+    ///
+    /// For inputs in `0.0..=1.0`, this produces the same `x`, `y` and `z` fields as
+    /// `Vector::new` for every `f32` value. The `w` field differs for a single input,
+    /// `0.16666666`, where `0.49999997 + 0.5` rounds up to `1.0` in `f32`. Implementations
+    /// that round ties to even, like `gl_reference_pack`, differ from both at exact halves.
+    ///
+    /// ```
+    /// use vec_2_10_10_10::Vector;
+    ///
+    /// assert_eq!(
+    ///     Vector::new_gl_compatible(0.444, 0.555, 0.666, 1.0).raw_value(),
+    ///     Vector::new(0.444, 0.555, 0.666, 1.0).raw_value()
+    /// );
+    ///
+    /// assert_eq!(Vector::new_gl_compatible(0.0, 0.0, 0.0, 0.16666666).alpha_level(), 1);
+    /// assert_eq!(Vector::new(0.0, 0.0, 0.0, 0.16666666).alpha_level(), 0);
+    /// ```
+    pub fn new_gl_compatible(x: f32, y: f32, z: f32, w: f32) -> Vector {
+        fn field(value: f32, max: f32) -> u32 {
+            (clamp(value) * max + 0.5) as u32
+        }
+
+        let mut c: u32 = 0;
+        c |= field(w, 3f32) << 30;
+        c |= field(z, 1023f32) << 20;
+        c |= field(y, 1023f32) << 10;
+        c |= field(x, 1023f32);
+
+        Vector {
+            data: c
+        }
+    }
+
+    /// Creates a new Vector, dithering `x`, `y` and `z` with caller-supplied noise.
+    ///
+    /// Each of `x`, `y` and `z` is clamped to `0.0..=1.0`, scaled to `0.0..=1023.0`, and the
+    /// matching `noise` threshold in `0.0..=1.0` is added before rounding down. A threshold
+    /// of `0.5` gives the same result as `Vector::new`. Thresholds are treated as `0.0..1.0`:
+    /// `1.0` acts as the largest value below it. Values that are on a level, such as values
+    /// returned by the getters, stay on that level for any threshold, so only values between
+    /// levels are dithered. Sampling thresholds from a blue-noise texture per pixel spreads
+    /// the rounding error with the least visible pattern.
+    /// The `w` value is stored like in `Vector::closest`.
+    ///
+    /// ```
+    /// use vec_2_10_10_10::Vector;
+    ///
+    /// let value = Vector::new_blue_noise(0.5, 0.5, 0.5, 1.0, [0.5, 0.0, 1.0]);
+    /// assert_eq!(value.to_raw_fields(), [512, 511, 512, 3]);
+    ///
+    /// let white = Vector::new_blue_noise(1.0, 1.0, 1.0, 1.0, [1.0, 1.0, 1.0]);
+    /// assert_eq!(white.raw_value(), Vector::OPAQUE_WHITE.raw_value());
+    ///
+    /// let black = Vector::new_blue_noise(0.0, 0.0, 0.0, 1.0, [1.0, 1.0, 1.0]);
+    /// assert_eq!(black.rgb_raw(), 0);
+    ///
+    /// let level = Vector::new_blue_noise(512.0 / 1023.0, 511.0 / 1023.0, 0.0, 1.0, [1.0, 0.0, 0.0]);
+    /// assert_eq!(level.to_raw_fields(), [512, 511, 0, 3]);
+    /// ```
+    pub fn new_blue_noise(x: f32, y: f32, z: f32, w: f32, noise: [f32; 3]) -> Vector {
+        fn field(value: f32, noise: f32) -> u32 {
+            let noise = clamp(noise).min(1.0 - f32::EPSILON);
+            // Computed in `f64`, so that a threshold just below `1.0` is not rounded up to the
+            // next level. Values within float error of a level are snapped onto it, otherwise
+            // `511.0 / 1023.0` would drop to level 510 with zero threshold.
+            let scaled = clamp(value) as f64 * 1023.0;
+            let level = scaled.round();
+            let scaled = if (scaled - level).abs() < 1e-4 { level } else { scaled };
+            ((scaled + noise as f64).floor() as u32).min(1023)
+        }
+
+        let mut c: u32 = 0;
+        c |= quantize_to(w, 3) << 30;
+        c |= field(z, noise[2]) << 20;
+        c |= field(y, noise[1]) << 10;
+        c |= field(x, noise[0]);
+
+        Vector {
+            data: c
+        }
+    }
+
+    /// Same as `new`, without the debug range check.
+    fn new_clamped(x: f32, y: f32, z: f32, w: f32) -> Vector {
+        Vector {
+            data: pack4::<10, 10, 10, 2>(x, y, z, w)
+        }
+    }
+
     /// Creates a vector from raw 4-byte data.
     ///
     /// The vector can be used to inspect such data if it was created by other means.
     ///
     /// ```
-    /// let other_value = *vec_2_10_10_10::Vector::new(0.444, 0.555, 0.666, 0.333).raw_value();
+    /// let other_value = vec_2_10_10_10::Vector::new(0.444, 0.555, 0.666, 0.333).raw_value();
     /// let value = vec_2_10_10_10::Vector::from_raw(other_value);
     ///
     /// assert!(approx_equal(value.x(), 0.444));
@@ -112,200 +381,3324 @@ impl Vector {
     ///     a > b - DELTA && a < b + DELTA
     /// }
     /// ```
-    pub fn from_raw(data: u32) -> Vector {
+    pub const fn from_raw(data: u32) -> Vector {
         Vector {
-            data: data
+            data
         }
     }
 
-    /// Get `x` value.
-    pub fn x(&self) -> f32 {
-        (1023 & self.data) as f32 / 1023f32
+    /// Creates an opaque vector from raw 4-byte data that has no alpha.
+    ///
+    /// The low 30 bits are used as `x`, `y` and `z`, and `w` is forced to `1.0`
+    /// by ORing in `3 << 30`, whatever top 2 bits were set before.
+    ///
+    /// ```
+    /// let rgb = vec_2_10_10_10::Vector::new(0.444, 0.555, 0.666, 0.0).raw_value();
+    /// let value = vec_2_10_10_10::Vector::from_rgb_raw(rgb);
+    ///
+    /// assert_eq!(value.raw_value(), rgb | 3 << 30);
+    /// assert_eq!(value.w(), 1.0);
+    /// ```
+    pub fn from_rgb_raw(data: u32) -> Vector {
+        Vector {
+            data: data | 3 << 30
+        }
     }
 
-    /// Get `y` value.
-    pub fn y(&self) -> f32 {
-        ((1023 << 10 & self.data) >> 10) as f32 / 1023f32
+    /// Creates a new Vector with `w` set to `1.0` if `opaque`, and to `0.0` otherwise.
+    ///
+    /// The `r`, `g` and `b` values are stored like in `Vector::new`.
+    ///
+    /// ```
+    /// let opaque = vec_2_10_10_10::Vector::from_rgb_opaque(0.2, 0.4, 0.6, true);
+    /// let transparent = vec_2_10_10_10::Vector::from_rgb_opaque(0.2, 0.4, 0.6, false);
+    ///
+    /// assert_eq!(opaque.w(), 1.0);
+    /// assert_eq!(transparent.w(), 0.0);
+    /// assert!(opaque.same_rgb(&transparent));
+    /// ```
+    pub fn from_rgb_opaque(r: f32, g: f32, b: f32, opaque: bool) -> Vector {
+        Vector::new(r, g, b, if opaque { 1.0 } else { 0.0 })
     }
 
-    /// Get `z` value.
-    pub fn z(&self) -> f32 {
-        ((1023 << 20 & self.data) >> 20) as f32 / 1023f32
+    /// Creates a new Vector from signed values in `-1.0..=1.0` range.
+    ///
+    /// Every value `v` is remapped to `(v + 1.0) / 2.0` and then stored like in `Vector::new`,
+    /// so `-1.0` is stored as `0.0`, `0.0` as `0.5` and `1.0` as `1.0`. Values outside
+    /// `-1.0..=1.0` are clamped.
+    ///
+    /// This is not a signed format: use `to_snorm` to map the values back.
+    ///
+    /// ```
+    /// let value = vec_2_10_10_10::Vector::new_snorm(-1.0, 0.0, 0.5, 1.0);
+    /// let [x, y, z, w] = value.to_snorm();
+    ///
+    /// assert!(approx_equal(x, -1.0));
+    /// assert!(approx_equal(y, 0.0));
+    /// assert!(approx_equal(z, 0.5));
+    /// assert!(approx_equal(w, 1.0));
+    /// #
+    /// # fn approx_equal(a: f32, b: f32) -> bool {
+    /// #     const DELTA: f32 = 0.001;
+    /// #     a > b - DELTA && a < b + DELTA
+    /// # }
+    /// ```
+    pub fn new_snorm(x: f32, y: f32, z: f32, w: f32) -> Vector {
+        Vector::new_clamped(
+            (x + 1.0) / 2.0,
+            (y + 1.0) / 2.0,
+            (z + 1.0) / 2.0,
+            (w + 1.0) / 2.0,
+        )
     }
 
-    /// Get `w` value.
-    pub fn w(&self) -> f32 {
-        ((0b11 << 30 & self.data) >> 30) as f32 / 3f32
+    /// Get `x`, `y`, `z` and `w` values mapped back to `-1.0..=1.0` range with `v * 2.0 - 1.0`.
+    ///
+    /// This is the reverse of `new_snorm`.
+    pub fn to_snorm(&self) -> [f32; 4] {
+        [
+            self.x() * 2.0 - 1.0,
+            self.y() * 2.0 - 1.0,
+            self.z() * 2.0 - 1.0,
+            self.w() * 2.0 - 1.0,
+        ]
     }
 
-    /// Update `x` value.
+    /// Creates a new Vector from octahedral-encoded normal and an extra value.
     ///
-    /// This changes internal 4-byte representation.
+    /// The `oct` components in `-1.0..=1.0` are remapped to `0.0..=1.0` with `(v + 1.0) / 2.0`
+    /// and stored in `x` and `y`. The `extra` value in `0.0..=1.0` is stored in `z`.
+    /// The `w` value is set to `0.0` and is free for other use, like `set_alpha_level`.
+    /// Out of range values are clamped.
     ///
     /// ```
-    /// let mut value = vec_2_10_10_10::Vector::new(0.0, 0.0, 0.0, 0.0);
-    /// value.set_x(0.333);
+    /// let value = vec_2_10_10_10::Vector::from_octahedral([-0.5, 0.25], 0.75);
+    /// let (oct, extra) = value.to_octahedral();
     ///
-    /// assert!(approx_equal(value.x(), 0.333));
-    /// assert!(approx_equal(value.y(), 0.0));
-    /// assert!(approx_equal(value.z(), 0.0));
-    /// assert!(approx_equal(value.w(), 0.0));
+    /// assert!(approx_equal(oct[0], -0.5));
+    /// assert!(approx_equal(oct[1], 0.25));
+    /// assert!(approx_equal(extra, 0.75));
     /// #
     /// # fn approx_equal(a: f32, b: f32) -> bool {
-    /// #     const DELTA: f32 = 0.001;
+    /// #     const DELTA: f32 = 0.002;
     /// #     a > b - DELTA && a < b + DELTA
     /// # }
     /// ```
-    pub fn set_x(&mut self, x: f32) {
-        let x = (clamp(x) * 1023f32).round() as u32;
-        let mut c: u32 =
-            (
-                3 << 30 | 1023 << 20 | 1023 << 10
-            ) & self.data;
-        c |= x;
-        self.data = c;
+    pub fn from_octahedral(oct: [f32; 2], extra: f32) -> Vector {
+        Vector::new_clamped((oct[0] + 1.0) / 2.0, (oct[1] + 1.0) / 2.0, extra, 0.0)
     }
 
-    /// Update `y` value.
+    /// Get octahedral-encoded normal and extra value stored by `from_octahedral`.
+    pub fn to_octahedral(&self) -> ([f32; 2], f32) {
+        ([self.x() * 2.0 - 1.0, self.y() * 2.0 - 1.0], self.z())
+    }
+
+    /// Creates a new Vector from two 2D coordinates in `0.0..=1.0` range.
     ///
-    /// This changes internal 4-byte representation.
+    /// The first pair is stored in `x` and `y`, the second pair in `z` and `w`. Values are
+    /// clamped to `0.0..=1.0`.
+    ///
+    /// Note that `v1` is stored in the 2-bit `w`, so it can only be `0.0`, `0.3(3)`, `0.6(6)`
+    /// or `1.0`, while the other three coordinates have 1024 levels.
     ///
     /// ```
-    /// let mut value = vec_2_10_10_10::Vector::new(0.0, 0.0, 0.0, 0.0);
-    /// value.set_y(0.333);
+    /// let value = vec_2_10_10_10::Vector::from_uv_pair(0.25, 0.5, 0.75, 0.7);
+    /// let [u0, v0, u1, v1] = value.to_uv_pair();
     ///
-    /// assert!(approx_equal(value.x(), 0.0));
-    /// assert!(approx_equal(value.y(), 0.333));
-    /// assert!(approx_equal(value.z(), 0.0));
-    /// assert!(approx_equal(value.w(), 0.0));
+    /// assert!(approx_equal(u0, 0.25));
+    /// assert!(approx_equal(v0, 0.5));
+    /// assert!(approx_equal(u1, 0.75));
+    /// assert!(approx_equal(v1, 0.666));
     /// #
     /// # fn approx_equal(a: f32, b: f32) -> bool {
     /// #     const DELTA: f32 = 0.001;
     /// #     a > b - DELTA && a < b + DELTA
     /// # }
     /// ```
-    pub fn set_y(&mut self, y: f32) {
-        let y = (clamp(y) * 1023f32).round() as u32;
-        let mut c: u32 =
-            (
-                3 << 30 | 1023 << 20 | 1023
-            ) & self.data;
-        c |= y << 10;
-        self.data = c;
+    pub fn from_uv_pair(u0: f32, v0: f32, u1: f32, v1: f32) -> Vector {
+        Vector::new_clamped(u0, v0, u1, v1)
     }
 
-    /// Update `z` value.
+    /// Get `[u0, v0, u1, v1]` coordinates stored by `from_uv_pair`.
+    pub fn to_uv_pair(&self) -> [f32; 4] {
+        self.to_normalized()
+    }
+
+    /// Creates a new Vector from tangent-space normal and bitangent sign.
     ///
-    /// This changes internal 4-byte representation.
+    /// The `normal` components in `-1.0..=1.0` are stored like in `new_snorm`. The `w` value
+    /// is `1.0` if `sign >= 0.0`, and `0.0` otherwise.
     ///
     /// ```
-    /// let mut value = vec_2_10_10_10::Vector::new(0.0, 0.0, 0.0, 0.0);
-    /// value.set_z(0.333);
+    /// let value = vec_2_10_10_10::Vector::from_tangent_normal([0.0, 0.6, -0.8], -1.0);
+    /// let (normal, sign) = value.to_tangent_normal();
     ///
-    /// assert!(approx_equal(value.x(), 0.0));
-    /// assert!(approx_equal(value.y(), 0.0));
-    /// assert!(approx_equal(value.z(), 0.333));
-    /// assert!(approx_equal(value.w(), 0.0));
+    /// assert!(approx_equal(normal[0], 0.0));
+    /// assert!(approx_equal(normal[1], 0.6));
+    /// assert!(approx_equal(normal[2], -0.8));
+    /// assert_eq!(sign, -1.0);
     /// #
     /// # fn approx_equal(a: f32, b: f32) -> bool {
-    /// #     const DELTA: f32 = 0.001;
+    /// #     const DELTA: f32 = 0.002;
     /// #     a > b - DELTA && a < b + DELTA
     /// # }
     /// ```
-    pub fn set_z(&mut self, z: f32) {
-        let z = (clamp(z) * 1023f32).round() as u32;
-        let mut c: u32 =
-            (
-                3 << 30 | 1023 << 10 | 1023
-            ) & self.data;
-        c |= z << 20;
-        self.data = c;
+    pub fn from_tangent_normal(normal: [f32; 3], sign: f32) -> Vector {
+        Vector::new_clamped(
+            (normal[0] + 1.0) / 2.0,
+            (normal[1] + 1.0) / 2.0,
+            (normal[2] + 1.0) / 2.0,
+            if sign >= 0.0 { 1.0 } else { 0.0 },
+        )
     }
 
-    /// Update `x`, `y` and `z`.
+    /// Get tangent-space normal and bitangent sign stored by `from_tangent_normal`.
     ///
-    /// This changes internal 4-byte representation.
+    /// The sign is `1.0` if `w` is at least `0.5`, and `-1.0` otherwise.
+    pub fn to_tangent_normal(&self) -> ([f32; 3], f32) {
+        let [x, y, z, _] = self.to_snorm();
+        ([x, y, z], if self.w() >= 0.5 { 1.0 } else { -1.0 })
+    }
+
+    /// Creates a new Vector from unsigned `x` and `y`, and signed `z`, storing the sign of `z` in `w`.
+    ///
+    /// The `x` and `y` values are clamped to `0.0..=1.0`. The `z.abs()` value is clamped to
+    /// `0.0..=1.0` and stored in `z`, and `w` is `0.0` for negative `z` and `1.0` otherwise.
+    /// Unlike `new_snorm`, this keeps the full 10-bit precision for the magnitude of `z`.
     ///
     /// ```
-    /// let mut value = vec_2_10_10_10::Vector::new(0.0, 0.0, 0.0, 0.0);
-    /// value.set_xyz(0.333, 0.444, 0.555);
+    /// let value = vec_2_10_10_10::Vector::from_xy_signed_z(0.25, 0.5, -0.75);
+    /// let [x, y, z] = value.to_xy_signed_z();
     ///
-    /// assert!(approx_equal(value.x(), 0.333));
-    /// assert!(approx_equal(value.y(), 0.444));
-    /// assert!(approx_equal(value.z(), 0.555));
-    /// assert!(approx_equal(value.w(), 0.0));
+    /// assert!(approx_equal(x, 0.25));
+    /// assert!(approx_equal(y, 0.5));
+    /// assert!(approx_equal(z, -0.75));
+    /// assert_eq!(value.w(), 0.0);
     /// #
     /// # fn approx_equal(a: f32, b: f32) -> bool {
     /// #     const DELTA: f32 = 0.001;
     /// #     a > b - DELTA && a < b + DELTA
     /// # }
     /// ```
-    pub fn set_xyz(&mut self, x: f32, y: f32, z: f32) {
-        let x = (clamp(x) * 1023f32).round() as u32;
-        let y = (clamp(y) * 1023f32).round() as u32;
-        let z = (clamp(z) * 1023f32).round() as u32;
-        let mut c: u32 =
-            (
-                3 << 30
-            ) & self.data;
-        c |= z << 20;
-        c |= y << 10;
-        c |= x << 0;
-        self.data = c;
+    pub fn from_xy_signed_z(x: f32, y: f32, z: f32) -> Vector {
+        Vector::new_clamped(x, y, z.abs(), if z < 0.0 { 0.0 } else { 1.0 })
     }
 
-    /// Update `w`.
+    /// Get `[x, y, z]` values stored by `from_xy_signed_z`, with the sign of `z` restored from `w`.
     ///
-    /// This changes internal 4-byte representation.
+    /// The `z` value is negative if `w` is less than `0.5`.
+    pub fn to_xy_signed_z(&self) -> [f32; 3] {
+        let z = if self.w() < 0.5 { -self.z() } else { self.z() };
+        [self.x(), self.y(), z]
+    }
+
+    /// Creates a new Vector from normalized position and a small id `0..=3`, such as material id.
+    ///
+    /// The `pos` components are clamped to `0.0..=1.0` and stored in `x`, `y` and `z`.
+    /// The `id` is stored as raw `w` level, and only its low 2 bits are used.
     ///
     /// ```
-    /// let mut value = vec_2_10_10_10::Vector::new(0.0, 0.0, 0.0, 0.0);
-    /// value.set_w(0.333);
+    /// let value = vec_2_10_10_10::Vector::from_position_id([0.25, 0.5, 1.0], 2);
+    /// let (pos, id) = value.to_position_id();
     ///
-    /// assert!(approx_equal(value.x(), 0.0));
-    /// assert!(approx_equal(value.y(), 0.0));
-    /// assert!(approx_equal(value.z(), 0.0));
-    /// assert!(approx_equal(value.w(), 0.333));
+    /// assert!(approx_equal(pos[0], 0.25));
+    /// assert!(approx_equal(pos[1], 0.5));
+    /// assert_eq!(pos[2], 1.0);
+    /// assert_eq!(id, 2);
     /// #
     /// # fn approx_equal(a: f32, b: f32) -> bool {
     /// #     const DELTA: f32 = 0.001;
     /// #     a > b - DELTA && a < b + DELTA
     /// # }
     /// ```
-    pub fn set_w(&mut self, w: f32) {
-        let w = (clamp(w) * 3f32).round() as u32;
-        let mut c: u32 =
-            (
-                1023 << 20 | 1023 << 10 | 1023
-            ) & self.data;
-        c |= w << 30;
-        self.data = c;
+    pub fn from_position_id(pos: [f32; 3], id: u8) -> Vector {
+        let mut value = Vector::new_clamped(pos[0], pos[1], pos[2], 0.0);
+        value.set_alpha_level(id);
+        value
     }
 
-    /// Return raw internal value.
-    pub fn raw_value(&self) -> &u32 {
-        &self.data
+    /// Get normalized position and id stored by `from_position_id`.
+    pub fn to_position_id(&self) -> ([f32; 3], u8) {
+        ([self.x(), self.y(), self.z()], self.alpha_level())
     }
-}
 
-impl fmt::Debug for Vector {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        f.debug_set()
-            .entry(&self.x())
-            .entry(&self.y())
-            .entry(&self.z())
-            .entry(&self.w())
-            .finish()
-    }
-}
+    /// Creates a new Vector from values that are already scaled to field units.
+    ///
+    /// The `x`, `y` and `z` values are expected in `0.0..=1023.0`, and `w2` in `0.0..=3.0`.
+    /// They are rounded to the nearest integer and clamped to the field range, without
+    /// dividing and multiplying again.
+    ///
+    /// ```
+    /// let value = vec_2_10_10_10::Vector::from_u10_scaled(511.6, 1023.0, 2000.0, 2.2);
+    ///
+    /// assert_eq!(value.to_raw_fields(), [512, 1023, 1023, 2]);
+    /// ```
+    pub fn from_u10_scaled(x: f32, y: f32, z: f32, w2: f32) -> Vector {
+        fn field(value: f32, max: f32) -> u32 {
+            value.round().max(0.0).min(max) as u32
+        }
 
-#[inline]
-fn clamp(c: f32) -> f32 {
-    if c < 0.0 {
-        return 0.0;
-    }
-    if c > 1.0 {
-        return 1.0;
+        let mut c: u32 = 0;
+        c |= field(w2, 3f32) << 30;
+        c |= field(z, 1023f32) << 20;
+        c |= field(y, 1023f32) << 10;
+        c |= field(x, 1023f32);
+
+        Vector {
+            data: c
+        }
     }
-    c
-}
\ No newline at end of file
+
+    /// Creates an opaque vector from 16-bit RGB565 color.
+    ///
+    /// Red is taken from the top 5 bits, green from the middle 6 bits and blue from
+    /// the low 5 bits. The `w` value is set to `1.0`.
+    ///
+    /// ```
+    /// let value = vec_2_10_10_10::Vector::new(0.444, 0.555, 0.666, 0.333);
+    /// let preview = vec_2_10_10_10::Vector::from_rgb565(value.to_rgb565());
+    ///
+    /// assert_eq!(preview.to_rgb565(), value.to_rgb565());
+    /// assert!(approx_equal(preview.x(), 0.444));
+    /// assert!(approx_equal(preview.w(), 1.0));
+    /// #
+    /// # fn approx_equal(a: f32, b: f32) -> bool {
+    /// #     const DELTA: f32 = 0.02;
+    /// #     a > b - DELTA && a < b + DELTA
+    /// # }
+    /// ```
+    pub fn from_rgb565(rgb: u16) -> Vector {
+        Vector::new_clamped(
+            (rgb >> 11 & 31) as f32 / 31f32,
+            (rgb >> 5 & 63) as f32 / 63f32,
+            (rgb & 31) as f32 / 31f32,
+            1.0,
+        )
+    }
+
+    /// Creates a new Vector from linear RGB color, storing it sRGB-encoded.
+    ///
+    /// The sRGB transfer function is applied to `r`, `g` and `b` before packing.
+    /// The alpha `a` is stored as is. Values are clamped to `0.0..=1.0`.
+    ///
+    /// ```
+    /// let value = vec_2_10_10_10::Vector::from_linear_srgb(0.2140, 0.0, 1.0, 1.0);
+    ///
+    /// assert!(approx_equal(value.x(), 0.5));
+    /// assert!(approx_equal(value.z(), 1.0));
+    ///
+    /// let [r, g, b, a] = value.to_linear_srgb();
+    /// assert!(approx_equal(r, 0.2140));
+    /// assert!(approx_equal(g, 0.0));
+    /// assert!(approx_equal(b, 1.0));
+    /// assert!(approx_equal(a, 1.0));
+    /// #
+    /// # fn approx_equal(a: f32, b: f32) -> bool {
+    /// #     const DELTA: f32 = 0.001;
+    /// #     a > b - DELTA && a < b + DELTA
+    /// # }
+    /// ```
+    pub fn from_linear_srgb(r: f32, g: f32, b: f32, a: f32) -> Vector {
+        Vector::new_clamped(
+            srgb_encode(clamp(r)),
+            srgb_encode(clamp(g)),
+            srgb_encode(clamp(b)),
+            a,
+        )
+    }
+
+    /// Get `[r, g, b, a]` linear color, decoding sRGB-encoded `x`, `y` and `z`.
+    ///
+    /// This is the reverse of `from_linear_srgb`. The `w` value is returned as is.
+    pub fn to_linear_srgb(&self) -> [f32; 4] {
+        [
+            srgb_decode(self.x()),
+            srgb_decode(self.y()),
+            srgb_decode(self.z()),
+            self.w(),
+        ]
+    }
+
+    /// Creates a new Vector from color with alpha already premultiplied into `r`, `g` and `b`.
+    ///
+    /// The values are stored as is, clamped to `0.0..=1.0`. Use `to_premultiplied`
+    /// to get them back.
+    ///
+    /// Note that `a` is quantized to one of 4 levels, while `r`, `g` and `b` were
+    /// premultiplied with the original `a`. Dividing them by the stored `a` does not
+    /// restore the original color unless `a` was exactly `0.0`, `1/3`, `2/3` or `1.0`,
+    /// and with low `a` only a small part of the 1024 levels is used.
+    ///
+    /// ```
+    /// let value = vec_2_10_10_10::Vector::from_premultiplied(0.2, 0.1, 0.0, 0.333);
+    /// let [r, g, b, a] = value.to_premultiplied();
+    ///
+    /// assert!(approx_equal(r, 0.2));
+    /// assert!(approx_equal(g, 0.1));
+    /// assert!(approx_equal(b, 0.0));
+    /// assert!(approx_equal(a, 0.333));
+    /// #
+    /// # fn approx_equal(a: f32, b: f32) -> bool {
+    /// #     const DELTA: f32 = 0.001;
+    /// #     a > b - DELTA && a < b + DELTA
+    /// # }
+    /// ```
+    pub fn from_premultiplied(r: f32, g: f32, b: f32, a: f32) -> Vector {
+        Vector::new_clamped(r, g, b, a)
+    }
+
+    /// Get `[r, g, b, a]` premultiplied color stored with `from_premultiplied`.
+    ///
+    /// The values are returned as decoded, without any division by `a`.
+    pub fn to_premultiplied(&self) -> [f32; 4] {
+        self.to_normalized()
+    }
+
+    /// Creates a new Vector from HDR color, tone mapping `r`, `g` and `b` with Reinhard curve.
+    ///
+    /// Every color value `c` is mapped to `c / (1.0 + c)`, so `0.0..` compresses into
+    /// `0.0..1.0` instead of clipping at `1.0`. Negative values are stored as `0.0`.
+    /// The `a` value is not tone mapped and is clamped to `0.0..=1.0`.
+    ///
+    /// ```
+    /// let value = vec_2_10_10_10::Vector::from_hdr_reinhard(1.0, 3.0, 0.0, 1.0);
+    ///
+    /// assert!(approx_equal(value.x(), 0.5));
+    /// assert!(approx_equal(value.y(), 0.75));
+    /// assert_eq!(value.z(), 0.0);
+    /// assert_eq!(value.w(), 1.0);
+    /// #
+    /// # fn approx_equal(a: f32, b: f32) -> bool {
+    /// #     const DELTA: f32 = 0.001;
+    /// #     a > b - DELTA && a < b + DELTA
+    /// # }
+    /// ```
+    pub fn from_hdr_reinhard(r: f32, g: f32, b: f32, a: f32) -> Vector {
+        fn reinhard(c: f32) -> f32 {
+            let c = c.max(0.0);
+            1.0 - 1.0 / (1.0 + c)
+        }
+
+        Vector::new_clamped(reinhard(r), reinhard(g), reinhard(b), a)
+    }
+
+    /// Creates a new Vector from full-range BT.709 YCbCr color.
+    ///
+    /// All inputs are in `0.0..=1.0` range, with `cb` and `cr` offset so that `0.5` means
+    /// no chroma. The color is converted to RGB, which is stored in `x`, `y` and `z`,
+    /// and `alpha` is stored in `w`. Values are clamped to `0.0..=1.0`.
+    ///
+    /// ```
+    /// let value = vec_2_10_10_10::Vector::from_ycbcr(0.2126, 0.3854, 1.0, 1.0);
+    ///
+    /// assert!(approx_equal(value.x(), 1.0));
+    /// assert!(approx_equal(value.y(), 0.0));
+    /// assert!(approx_equal(value.z(), 0.0));
+    ///
+    /// let [y, cb, cr, alpha] = value.to_ycbcr();
+    /// assert!(approx_equal(y, 0.2126));
+    /// assert!(approx_equal(cb, 0.3854));
+    /// assert!(approx_equal(cr, 1.0));
+    /// assert_eq!(alpha, 1.0);
+    /// #
+    /// # fn approx_equal(a: f32, b: f32) -> bool {
+    /// #     const DELTA: f32 = 0.001;
+    /// #     a > b - DELTA && a < b + DELTA
+    /// # }
+    /// ```
+    pub fn from_ycbcr(y: f32, cb: f32, cr: f32, alpha: f32) -> Vector {
+        let cb = cb - 0.5;
+        let cr = cr - 0.5;
+        Vector::new_clamped(
+            y + 1.5748 * cr,
+            y - 0.1873 * cb - 0.4681 * cr,
+            y + 1.8556 * cb,
+            alpha,
+        )
+    }
+
+    /// Get `[y, cb, cr, alpha]` full-range BT.709 YCbCr color.
+    ///
+    /// This is the reverse of `from_ycbcr`.
+    pub fn to_ycbcr(&self) -> [f32; 4] {
+        let y = self.luminance();
+        [
+            y,
+            (self.z() - y) / 1.8556 + 0.5,
+            (self.x() - y) / 1.5748 + 0.5,
+            self.w(),
+        ]
+    }
+
+    /// Creates an opaque Vector with approximate color of visible light of the given wavelength.
+    ///
+    /// Uses the piecewise linear approximation by Dan Bruton, with intensity falling off
+    /// near the edges of the visible range and gamma `0.8`. The result is only meant for
+    /// visualization, not for colorimetry. Wavelengths outside `380.0..=750.0` nanometers
+    /// are black.
+    ///
+    /// ```
+    /// use vec_2_10_10_10::Vector;
+    ///
+    /// let green = Vector::from_wavelength(530.0);
+    /// assert!(green.y() == 1.0 && green.x() < 0.5 && green.z() == 0.0);
+    /// assert_eq!(green.w(), 1.0);
+    ///
+    /// assert_eq!(Vector::from_wavelength(1000.0).raw_value(), 3 << 30);
+    /// ```
+    pub fn from_wavelength(nm: f32) -> Vector {
+        let (r, g, b) = match nm {
+            nm if (380.0..440.0).contains(&nm) => ((440.0 - nm) / 60.0, 0.0, 1.0),
+            nm if (440.0..490.0).contains(&nm) => (0.0, (nm - 440.0) / 50.0, 1.0),
+            nm if (490.0..510.0).contains(&nm) => (0.0, 1.0, (510.0 - nm) / 20.0),
+            nm if (510.0..580.0).contains(&nm) => ((nm - 510.0) / 70.0, 1.0, 0.0),
+            nm if (580.0..645.0).contains(&nm) => (1.0, (645.0 - nm) / 65.0, 0.0),
+            nm if (645.0..=750.0).contains(&nm) => (1.0, 0.0, 0.0),
+            _ => return Vector::from_raw(3 << 30),
+        };
+
+        let intensity = if nm < 420.0 {
+            0.3 + 0.7 * (nm - 380.0) / 40.0
+        } else if nm > 700.0 {
+            0.3 + 0.7 * (750.0 - nm) / 50.0
+        } else {
+            1.0
+        };
+
+        let adjust = |c: f32| (c * intensity).powf(0.8);
+        Vector::new_clamped(adjust(r), adjust(g), adjust(b), 1.0)
+    }
+
+    /// Converts `x`, `y` and `z` to 16-bit RGB565 color.
+    ///
+    /// Each value is rounded to the nearest 5-bit (red, blue) or 6-bit (green) level,
+    /// the same way `quantize` does it. The `w` value is dropped.
+    ///
+    /// ```
+    /// assert_eq!(vec_2_10_10_10::Vector::new(1.0, 0.0, 0.0, 1.0).to_rgb565(), 0b11111_000000_00000);
+    /// assert_eq!(vec_2_10_10_10::Vector::new(0.0, 1.0, 0.0, 1.0).to_rgb565(), 0b00000_111111_00000);
+    /// assert_eq!(vec_2_10_10_10::Vector::new(0.0, 0.0, 1.0, 1.0).to_rgb565(), 0b00000_000000_11111);
+    /// ```
+    pub fn to_rgb565(&self) -> u16 {
+        let r = quantize(self.x(), 5) as u16;
+        let g = quantize(self.y(), 6) as u16;
+        let b = quantize(self.z(), 5) as u16;
+        r << 11 | g << 5 | b
+    }
+
+    /// Creates a new Vector from 16-bit `[r, g, b, a]` fields, rounding to the nearest level.
+    ///
+    /// ```
+    /// let value = vec_2_10_10_10::Vector::from_rgba16([65535, 32768, 0, 43690]);
+    /// assert_eq!(value.to_raw_fields(), [1023, 512, 0, 2]);
+    /// ```
+    pub fn from_rgba16(rgba: [u16; 4]) -> Vector {
+        fn field(value: u16, max: u32) -> u16 {
+            ((value as u32 * max + 32767) / 65535) as u16
+        }
+
+        Vector::from_raw_fields([field(rgba[0], 1023), field(rgba[1], 1023), field(rgba[2], 1023), field(rgba[3], 3)])
+    }
+
+    /// Get `[r, g, b, a]` fields scaled to the full 16-bit range.
+    ///
+    /// Raw fields are scaled so that the largest level becomes `65535`, rounding to the
+    /// nearest integer. The result still has only 1024 distinct levels for `r`, `g` and `b`
+    /// and 4 for `a`: scaling does not add precision. `from_rgba16` restores the same vector.
+    ///
+    /// ```
+    /// let value = vec_2_10_10_10::Vector::from_raw_fields([1023, 512, 0, 2]);
+    /// assert_eq!(value.to_rgba16(), [65535, 32800, 0, 43690]);
+    /// assert_eq!(vec_2_10_10_10::Vector::from_rgba16(value.to_rgba16()).raw_value(), value.raw_value());
+    /// ```
+    pub fn to_rgba16(&self) -> [u16; 4] {
+        fn field(value: u16, max: u32) -> u16 {
+            ((value as u32 * 65535 + max / 2) / max) as u16
+        }
+
+        let [x, y, z, w] = self.to_raw_fields();
+        [field(x, 1023), field(y, 1023), field(z, 1023), field(w, 3)]
+    }
+
+    /// Get `x` value.
+    pub fn x(&self) -> f32 {
+        (1023 & self.data) as f32 / 1023f32
+    }
+
+    /// Get `y` value.
+    pub fn y(&self) -> f32 {
+        ((1023 << 10 & self.data) >> 10) as f32 / 1023f32
+    }
+
+    /// Get `z` value.
+    pub fn z(&self) -> f32 {
+        ((1023 << 20 & self.data) >> 20) as f32 / 1023f32
+    }
+
+    /// Get `w` value.
+    pub fn w(&self) -> f32 {
+        ((0b11 << 30 & self.data) >> 30) as f32 / 3f32
+    }
+
+    /// Write `x`, `y`, `z` and `w` values into provided array.
+    ///
+    /// Useful in hot loops where the same buffer is reused for many vectors.
+    ///
+    /// ```
+    /// let value = vec_2_10_10_10::Vector::new(0.444, 0.555, 0.666, 0.333);
+    ///
+    /// let mut out = [0.0; 4];
+    /// value.decode_into(&mut out);
+    ///
+    /// assert_eq!(out, [value.x(), value.y(), value.z(), value.w()]);
+    /// ```
+    pub fn decode_into(&self, out: &mut [f32; 4]) {
+        out[0] = self.x();
+        out[1] = self.y();
+        out[2] = self.z();
+        out[3] = self.w();
+    }
+
+    /// Add `x`, `y`, `z` and `w` values to a running `f64` sum.
+    ///
+    /// Summing in `f64` keeps averages of millions of vectors accurate, where a `f32`
+    /// sum would lose precision. Use `finish_average` to get the average back.
+    ///
+    /// ```
+    /// use vec_2_10_10_10::Vector;
+    ///
+    /// let mut acc = [0.0f64; 4];
+    /// Vector::new(1.0, 0.0, 0.5, 1.0).add_to_accumulator(&mut acc);
+    /// Vector::new(0.0, 0.0, 0.5, 0.333).add_to_accumulator(&mut acc);
+    ///
+    /// let average = Vector::finish_average(&acc, 2);
+    /// assert!(approx_equal(average.x(), 0.5));
+    /// assert!(approx_equal(average.z(), 0.5));
+    /// assert!(approx_equal(average.w(), 0.666));
+    /// #
+    /// # fn approx_equal(a: f32, b: f32) -> bool {
+    /// #     const DELTA: f32 = 0.001;
+    /// #     a > b - DELTA && a < b + DELTA
+    /// # }
+    /// ```
+    pub fn add_to_accumulator(&self, acc: &mut [f64; 4]) {
+        acc[0] += self.x() as f64;
+        acc[1] += self.y() as f64;
+        acc[2] += self.z() as f64;
+        acc[3] += self.w() as f64;
+    }
+
+    /// Creates a new Vector from a sum of `count` vectors filled by `add_to_accumulator`.
+    ///
+    /// Returns `Vector::TRANSPARENT_BLACK` if `count` is `0`.
+    pub fn finish_average(acc: &[f64; 4], count: usize) -> Vector {
+        if count == 0 {
+            return Vector::TRANSPARENT_BLACK;
+        }
+
+        let n = count as f64;
+        Vector::new_clamped(
+            (acc[0] / n) as f32,
+            (acc[1] / n) as f32,
+            (acc[2] / n) as f32,
+            (acc[3] / n) as f32,
+        )
+    }
+
+    /// Creates a vector from raw integer fields `[x, y, z, w]`.
+    ///
+    /// The `x`, `y` and `z` fields are masked to 10 bits, and `w` is masked to 2 bits.
+    ///
+    /// ```
+    /// let value = vec_2_10_10_10::Vector::from_raw_fields([1023, 512, 0, 3]);
+    ///
+    /// assert_eq!(value.to_raw_fields(), [1023, 512, 0, 3]);
+    /// assert_eq!(value.x(), 1.0);
+    /// assert_eq!(value.w(), 1.0);
+    ///
+    /// let masked = vec_2_10_10_10::Vector::from_raw_fields([1024, 1025, 2047, 4]);
+    /// assert_eq!(masked.to_raw_fields(), [0, 1, 1023, 0]);
+    /// ```
+    pub fn from_raw_fields(fields: [u16; 4]) -> Vector {
+        let mut c: u32 = 0;
+        c |= (fields[3] as u32 & 3) << 30;
+        c |= (fields[2] as u32 & 1023) << 20;
+        c |= (fields[1] as u32 & 1023) << 10;
+        c |= fields[0] as u32 & 1023;
+
+        Vector {
+            data: c
+        }
+    }
+
+    /// Get raw integer fields `[x, y, z, w]`.
+    ///
+    /// The `x`, `y` and `z` fields are in `0..=1023`, and `w` is in `0..=3`.
+    pub fn to_raw_fields(&self) -> [u16; 4] {
+        [
+            (1023 & self.data) as u16,
+            ((1023 << 10 & self.data) >> 10) as u16,
+            ((1023 << 20 & self.data) >> 20) as u16,
+            ((0b11 << 30 & self.data) >> 30) as u16,
+        ]
+    }
+
+    /// Get raw integer fields as `(x, y, z, w)` tuple, for printing with `{:?}`.
+    ///
+    /// For a table of raw fields and decoded values, format `DebugFields` instead.
+    ///
+    /// ```
+    /// let value = vec_2_10_10_10::Vector::from_raw_fields([512, 0, 1023, 2]);
+    /// assert_eq!(format!("{:?}", value.debug_fields()), "(512, 0, 1023, 2)");
+    /// ```
+    pub fn debug_fields(&self) -> (u16, u16, u16, u8) {
+        let [x, y, z, w] = self.to_raw_fields();
+        (x, y, z, w as u8)
+    }
+
+    /// Creates a vector from unsigned integer attribute values `[x, y, z, w]`.
+    ///
+    /// The `x`, `y` and `z` values are masked to 10 bits, and `w` is masked to 2 bits.
+    /// This is the same as `from_raw_fields`, but takes `u32` values like `uvec4` in GLSL.
+    ///
+    /// ```
+    /// let value = vec_2_10_10_10::Vector::from_uvec4([7, 1023, 1024, 3]);
+    /// assert_eq!(value.as_uvec4(), [7, 1023, 0, 3]);
+    /// ```
+    pub fn from_uvec4(values: [u32; 4]) -> Vector {
+        // Truncating to `u16` keeps the low bits, which are all that `from_raw_fields` uses.
+        Vector::from_raw_fields([values[0] as u16, values[1] as u16, values[2] as u16, values[3] as u16])
+    }
+
+    /// Get raw integer fields `[x, y, z, w]` as `u32` values.
+    ///
+    /// This is what a shader reads when the attribute is declared as a non-normalized
+    /// unsigned integer `uvec4`. Same as `to_unnormalized`.
+    pub fn as_uvec4(&self) -> [u32; 4] {
+        self.to_unnormalized()
+    }
+
+    /// Get `[x, y, z, w]` values in `0.0..=1.0` range.
+    ///
+    /// This matches how GL reads the attribute when it is declared as normalized
+    /// (`glVertexAttribPointer` with `normalized` set to `GL_TRUE`).
+    ///
+    /// ```
+    /// let value = vec_2_10_10_10::Vector::new(1.0, 0.0, 0.5, 1.0);
+    ///
+    /// assert_eq!(value.to_normalized(), [value.x(), value.y(), value.z(), value.w()]);
+    /// ```
+    pub fn to_normalized(&self) -> [f32; 4] {
+        [self.x(), self.y(), self.z(), self.w()]
+    }
+
+    /// Get `[x, y, z, w]` raw integer fields.
+    ///
+    /// This matches how GL reads the attribute when it is not normalized: the
+    /// `x`, `y` and `z` values are in `0..=1023`, and `w` is in `0..=3`.
+    ///
+    /// ```
+    /// let value = vec_2_10_10_10::Vector::new(1.0, 0.0, 0.5, 1.0);
+    ///
+    /// assert_eq!(value.to_unnormalized(), [1023, 0, 512, 3]);
+    /// ```
+    pub fn to_unnormalized(&self) -> [u32; 4] {
+        let fields = self.to_raw_fields();
+        [fields[0] as u32, fields[1] as u32, fields[2] as u32, fields[3] as u32]
+    }
+
+    /// Update `x` value.
+    ///
+    /// This changes internal 4-byte representation.
+    ///
+    /// ```
+    /// let mut value = vec_2_10_10_10::Vector::new(0.0, 0.0, 0.0, 0.0);
+    /// value.set_x(0.333);
+    ///
+    /// assert!(approx_equal(value.x(), 0.333));
+    /// assert!(approx_equal(value.y(), 0.0));
+    /// assert!(approx_equal(value.z(), 0.0));
+    /// assert!(approx_equal(value.w(), 0.0));
+    /// #
+    /// # fn approx_equal(a: f32, b: f32) -> bool {
+    /// #     const DELTA: f32 = 0.001;
+    /// #     a > b - DELTA && a < b + DELTA
+    /// # }
+    /// ```
+    pub fn set_x(&mut self, x: f32) {
+        let x = quantize(x, 10);
+        let mut c: u32 =
+            (
+                3 << 30 | 1023 << 20 | 1023 << 10
+            ) & self.data;
+        c |= x;
+        self.data = c;
+    }
+
+    /// Update `y` value.
+    ///
+    /// This changes internal 4-byte representation.
+    ///
+    /// ```
+    /// let mut value = vec_2_10_10_10::Vector::new(0.0, 0.0, 0.0, 0.0);
+    /// value.set_y(0.333);
+    ///
+    /// assert!(approx_equal(value.x(), 0.0));
+    /// assert!(approx_equal(value.y(), 0.333));
+    /// assert!(approx_equal(value.z(), 0.0));
+    /// assert!(approx_equal(value.w(), 0.0));
+    /// #
+    /// # fn approx_equal(a: f32, b: f32) -> bool {
+    /// #     const DELTA: f32 = 0.001;
+    /// #     a > b - DELTA && a < b + DELTA
+    /// # }
+    /// ```
+    pub fn set_y(&mut self, y: f32) {
+        let y = quantize(y, 10);
+        let mut c: u32 =
+            (
+                3 << 30 | 1023 << 20 | 1023
+            ) & self.data;
+        c |= y << 10;
+        self.data = c;
+    }
+
+    /// Update `z` value.
+    ///
+    /// This changes internal 4-byte representation.
+    ///
+    /// ```
+    /// let mut value = vec_2_10_10_10::Vector::new(0.0, 0.0, 0.0, 0.0);
+    /// value.set_z(0.333);
+    ///
+    /// assert!(approx_equal(value.x(), 0.0));
+    /// assert!(approx_equal(value.y(), 0.0));
+    /// assert!(approx_equal(value.z(), 0.333));
+    /// assert!(approx_equal(value.w(), 0.0));
+    /// #
+    /// # fn approx_equal(a: f32, b: f32) -> bool {
+    /// #     const DELTA: f32 = 0.001;
+    /// #     a > b - DELTA && a < b + DELTA
+    /// # }
+    /// ```
+    pub fn set_z(&mut self, z: f32) {
+        let z = quantize(z, 10);
+        let mut c: u32 =
+            (
+                3 << 30 | 1023 << 10 | 1023
+            ) & self.data;
+        c |= z << 20;
+        self.data = c;
+    }
+
+    /// Update `x`, `y` and `z`.
+    ///
+    /// This changes internal 4-byte representation.
+    ///
+    /// ```
+    /// let mut value = vec_2_10_10_10::Vector::new(0.0, 0.0, 0.0, 0.0);
+    /// value.set_xyz(0.333, 0.444, 0.555);
+    ///
+    /// assert!(approx_equal(value.x(), 0.333));
+    /// assert!(approx_equal(value.y(), 0.444));
+    /// assert!(approx_equal(value.z(), 0.555));
+    /// assert!(approx_equal(value.w(), 0.0));
+    /// #
+    /// # fn approx_equal(a: f32, b: f32) -> bool {
+    /// #     const DELTA: f32 = 0.001;
+    /// #     a > b - DELTA && a < b + DELTA
+    /// # }
+    /// ```
+    pub fn set_xyz(&mut self, x: f32, y: f32, z: f32) {
+        let x = quantize(x, 10);
+        let y = quantize(y, 10);
+        let z = quantize(z, 10);
+        let mut c: u32 =
+            (
+                3 << 30
+            ) & self.data;
+        c |= z << 20;
+        c |= y << 10;
+        c |= x;
+        self.data = c;
+    }
+
+    /// Update `w`.
+    ///
+    /// This changes internal 4-byte representation.
+    ///
+    /// ```
+    /// let mut value = vec_2_10_10_10::Vector::new(0.0, 0.0, 0.0, 0.0);
+    /// value.set_w(0.333);
+    ///
+    /// assert!(approx_equal(value.x(), 0.0));
+    /// assert!(approx_equal(value.y(), 0.0));
+    /// assert!(approx_equal(value.z(), 0.0));
+    /// assert!(approx_equal(value.w(), 0.333));
+    /// #
+    /// # fn approx_equal(a: f32, b: f32) -> bool {
+    /// #     const DELTA: f32 = 0.001;
+    /// #     a > b - DELTA && a < b + DELTA
+    /// # }
+    /// ```
+    pub fn set_w(&mut self, w: f32) {
+        let w = quantize(w, 2);
+        let mut c: u32 =
+            (
+                1023 << 20 | 1023 << 10 | 1023
+            ) & self.data;
+        c |= w << 30;
+        self.data = c;
+    }
+
+    /// Get `w` value as raw level `0..=3`.
+    ///
+    /// Level `0` is `0.0`, `1` is `0.3(3)`, `2` is `0.6(6)` and `3` is `1.0`.
+    pub fn alpha_level(&self) -> u8 {
+        (self.data >> 30) as u8
+    }
+
+    /// Update `w` value from raw level `0..=3`.
+    ///
+    /// Only the low 2 bits of `level` are used.
+    ///
+    /// This changes internal 4-byte representation.
+    ///
+    /// ```
+    /// let mut value = vec_2_10_10_10::Vector::new(0.5, 0.5, 0.5, 0.0);
+    /// value.set_alpha_level(2);
+    ///
+    /// assert_eq!(value.alpha_level(), 2);
+    /// assert!(approx_equal(value.w(), 0.666));
+    /// assert!(approx_equal(value.x(), 0.5));
+    ///
+    /// value.set_alpha_level(5);
+    /// assert_eq!(value.alpha_level(), 1);
+    /// #
+    /// # fn approx_equal(a: f32, b: f32) -> bool {
+    /// #     const DELTA: f32 = 0.001;
+    /// #     a > b - DELTA && a < b + DELTA
+    /// # }
+    /// ```
+    pub fn set_alpha_level(&mut self, level: u8) {
+        let mut c: u32 =
+            (
+                1023 << 20 | 1023 << 10 | 1023
+            ) & self.data;
+        c |= (level as u32 & 3) << 30;
+        self.data = c;
+    }
+
+    /// Get bit `n` of the 2-bit `w` field as a flag.
+    ///
+    /// This allows to use `w` as storage for two boolean flags.
+    ///
+    /// Panics if `n` is not `0` or `1`.
+    ///
+    /// ```
+    /// let value = vec_2_10_10_10::Vector::new(0.5, 0.5, 0.5, 0.333);
+    ///
+    /// assert!(value.w_bit(0));
+    /// assert!(!value.w_bit(1));
+    /// ```
+    pub fn w_bit(&self, n: u8) -> bool {
+        assert!(n < 2, "w bit index must be 0 or 1, got {}", n);
+        self.data >> (30 + n as u32) & 1 == 1
+    }
+
+    /// Set bit `n` of the 2-bit `w` field to `value`.
+    ///
+    /// Panics if `n` is not `0` or `1`.
+    ///
+    /// This changes internal 4-byte representation.
+    ///
+    /// ```
+    /// let mut value = vec_2_10_10_10::Vector::new(0.5, 0.5, 0.5, 0.0);
+    /// value.set_w_bit(1, true);
+    ///
+    /// assert!(!value.w_bit(0));
+    /// assert!(value.w_bit(1));
+    /// assert_eq!(value.alpha_level(), 2);
+    ///
+    /// value.set_w_bit(1, false);
+    /// assert_eq!(value.alpha_level(), 0);
+    /// ```
+    pub fn set_w_bit(&mut self, n: u8, value: bool) {
+        assert!(n < 2, "w bit index must be 0 or 1, got {}", n);
+        let mask = 1 << (30 + n as u32);
+        if value {
+            self.data |= mask;
+        } else {
+            self.data &= !mask;
+        }
+    }
+
+    /// Update `x`, `y`, `z` and `w` with a closure.
+    ///
+    /// The values are decoded once, passed to the closure as `[x, y, z, w]`, and the
+    /// returned values are clamped and packed once. This is cheaper than calling
+    /// several setters one after another.
+    ///
+    /// This changes internal 4-byte representation.
+    ///
+    /// ```
+    /// let mut value = vec_2_10_10_10::Vector::new(0.2, 0.4, 0.6, 1.0);
+    /// value.modify(|[x, y, z, w]| [z, y, x, w * 0.5]);
+    ///
+    /// assert!(approx_equal(value.x(), 0.6));
+    /// assert!(approx_equal(value.y(), 0.4));
+    /// assert!(approx_equal(value.z(), 0.2));
+    /// assert!(approx_equal(value.w(), 0.666));
+    /// #
+    /// # fn approx_equal(a: f32, b: f32) -> bool {
+    /// #     const DELTA: f32 = 0.001;
+    /// #     a > b - DELTA && a < b + DELTA
+    /// # }
+    /// ```
+    pub fn modify<F: FnOnce([f32; 4]) -> [f32; 4]>(&mut self, f: F) {
+        *self = Vector::closest_array(&f(self.to_normalized()));
+    }
+
+    /// Returns a vector with decoded values of channels `a` and `b` swapped.
+    ///
+    /// Channels are `0` for `x`, `1` for `y`, `2` for `z` and `3` for `w`.
+    ///
+    /// Swapping any of `x`, `y` or `z` with `w` is not symmetric: the 10-bit value moved
+    /// into `w` is quantized to one of 4 levels, while the 2-bit value moved out of `w`
+    /// is stored exactly. Swapping twice does not restore the original vector.
+    ///
+    /// Panics if `a` or `b` is more than `3`.
+    ///
+    /// ```
+    /// let value = vec_2_10_10_10::Vector::new(0.2, 0.4, 0.6, 1.0);
+    ///
+    /// let swapped = value.swap_channels(0, 2);
+    /// assert!(approx_equal(swapped.x(), 0.6));
+    /// assert!(approx_equal(swapped.z(), 0.2));
+    ///
+    /// let swapped = value.swap_channels(1, 3);
+    /// assert_eq!(swapped.y(), 1.0);
+    /// assert!(approx_equal(swapped.w(), 0.333));
+    /// #
+    /// # fn approx_equal(a: f32, b: f32) -> bool {
+    /// #     const DELTA: f32 = 0.001;
+    /// #     a > b - DELTA && a < b + DELTA
+    /// # }
+    /// ```
+    pub fn swap_channels(&self, a: usize, b: usize) -> Vector {
+        assert!(a < 4 && b < 4, "channels must be in 0..=3, got {} and {}", a, b);
+        let mut values = self.to_normalized();
+        values.swap(a, b);
+        Vector::closest_array(&values)
+    }
+
+    /// Creates a vector by reading 4 bytes of raw data from a possibly unaligned pointer.
+    ///
+    /// The bytes are interpreted in native byte order, the same way they would be laid
+    /// out in memory for a `Vector` itself. This is useful for parsing packed vertex
+    /// streams where the value is not 4-byte aligned in the source buffer.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be valid for reads of 4 bytes. No alignment is required.
+    ///
+    /// ```
+    /// let value = vec_2_10_10_10::Vector::new(0.444, 0.555, 0.666, 0.333);
+    ///
+    /// let mut buffer = [0u8; 5];
+    /// buffer[1..].copy_from_slice(&value.raw_value().to_ne_bytes());
+    ///
+    /// let read = unsafe { vec_2_10_10_10::Vector::from_unaligned_ptr(buffer[1..].as_ptr()) };
+    ///
+    /// assert_eq!(read.raw_value(), value.raw_value());
+    /// ```
+    pub unsafe fn from_unaligned_ptr(ptr: *const u8) -> Vector {
+        Vector {
+            data: (ptr as *const u32).read_unaligned()
+        }
+    }
+
+    /// Get luminance of `x`, `y` and `z` values using Rec. 709 weights.
+    ///
+    /// This is `0.2126 * x + 0.7152 * y + 0.0722 * z`.
+    ///
+    /// ```
+    /// let value = vec_2_10_10_10::Vector::new(1.0, 1.0, 1.0, 0.0);
+    ///
+    /// assert!(approx_equal(value.luminance(), 1.0));
+    /// #
+    /// # fn approx_equal(a: f32, b: f32) -> bool {
+    /// #     const DELTA: f32 = 0.001;
+    /// #     a > b - DELTA && a < b + DELTA
+    /// # }
+    /// ```
+    pub fn luminance(&self) -> f32 {
+        self.luminance_with([0.2126, 0.7152, 0.0722])
+    }
+
+    /// Returns opaque black or opaque white, whichever is more readable as text over this color.
+    ///
+    /// The `x`, `y` and `z` values are treated as sRGB-encoded: they are decoded to linear,
+    /// and Rec. 709 luminance of the linear color is computed. Colors with luminance above
+    /// `0.5` get black text, others get `Vector::OPAQUE_WHITE`. The `w` value is ignored.
+    ///
+    /// ```
+    /// use vec_2_10_10_10::Vector;
+    ///
+    /// assert_eq!(Vector::new(1.0, 1.0, 0.8, 1.0).contrast_text().raw_value(), 3 << 30);
+    /// assert_eq!(
+    ///     Vector::new(0.2, 0.2, 0.6, 1.0).contrast_text().raw_value(),
+    ///     Vector::OPAQUE_WHITE.raw_value()
+    /// );
+    /// ```
+    pub fn contrast_text(&self) -> Vector {
+        let [r, g, b, _] = self.to_linear_srgb();
+        if 0.2126 * r + 0.7152 * g + 0.0722 * b > 0.5 {
+            Vector::from_raw(3 << 30)
+        } else {
+            Vector::OPAQUE_WHITE
+        }
+    }
+
+    /// Get luminance of `x`, `y` and `z` values using custom weights.
+    ///
+    /// This is `weights[0] * x + weights[1] * y + weights[2] * z`, so for example
+    /// Rec. 601 luma can be computed with `[0.299, 0.587, 0.114]`.
+    ///
+    /// ```
+    /// let value = vec_2_10_10_10::Vector::new(0.0, 1.0, 0.0, 0.0);
+    ///
+    /// assert!(approx_equal(value.luminance_with([0.299, 0.587, 0.114]), 0.587));
+    /// #
+    /// # fn approx_equal(a: f32, b: f32) -> bool {
+    /// #     const DELTA: f32 = 0.001;
+    /// #     a > b - DELTA && a < b + DELTA
+    /// # }
+    /// ```
+    pub fn luminance_with(&self, weights: [f32; 3]) -> f32 {
+        weights[0] * self.x() + weights[1] * self.y() + weights[2] * self.z()
+    }
+
+    /// Returns a desaturated copy of this vector.
+    ///
+    /// Each of `x`, `y` and `z` is linearly interpolated towards the luminance by `amount`,
+    /// where `0.0` leaves the color unchanged and `1.0` makes it fully gray. The `amount`
+    /// is clamped to `0.0..=1.0`. The `w` value is preserved.
+    ///
+    /// Luminance is computed with Rec. 709 weights: `0.2126 * x + 0.7152 * y + 0.0722 * z`.
+    ///
+    /// ```
+    /// let value = vec_2_10_10_10::Vector::new(1.0, 0.0, 0.0, 0.333);
+    ///
+    /// let unchanged = value.desaturate(0.0);
+    /// assert_eq!(unchanged.raw_value(), value.raw_value());
+    ///
+    /// let gray = value.desaturate(1.0);
+    /// assert!(approx_equal(gray.x(), 0.2126));
+    /// assert!(approx_equal(gray.y(), 0.2126));
+    /// assert!(approx_equal(gray.z(), 0.2126));
+    /// assert!(approx_equal(gray.w(), 0.333));
+    /// #
+    /// # fn approx_equal(a: f32, b: f32) -> bool {
+    /// #     const DELTA: f32 = 0.001;
+    /// #     a > b - DELTA && a < b + DELTA
+    /// # }
+    /// ```
+    pub fn desaturate(&self, amount: f32) -> Vector {
+        let amount = clamp(amount);
+        let (x, y, z) = (self.x(), self.y(), self.z());
+        let l = self.luminance();
+
+        let mut result = *self;
+        result.set_xyz(
+            x + (l - x) * amount,
+            y + (l - y) * amount,
+            z + (l - z) * amount,
+        );
+        result
+    }
+
+    /// Returns a copy of this vector with hue rotated by `degrees`.
+    ///
+    /// The `x`, `y` and `z` values are treated as RGB, converted to HSV, and the hue
+    /// is wrapped into `0.0..360.0` after rotation, so negative and large angles work.
+    /// The `w` value is preserved.
+    ///
+    /// ```
+    /// let red = vec_2_10_10_10::Vector::new(1.0, 0.0, 0.0, 1.0);
+    ///
+    /// let green = red.rotate_hue(120.0);
+    /// assert!(approx_equal(green.x(), 0.0));
+    /// assert!(approx_equal(green.y(), 1.0));
+    /// assert!(approx_equal(green.z(), 0.0));
+    /// assert!(approx_equal(green.w(), 1.0));
+    ///
+    /// let blue = red.rotate_hue(-120.0);
+    /// assert!(approx_equal(blue.x(), 0.0));
+    /// assert!(approx_equal(blue.y(), 0.0));
+    /// assert!(approx_equal(blue.z(), 1.0));
+    ///
+    /// assert_eq!(red.rotate_hue(480.0).raw_value(), green.raw_value());
+    /// #
+    /// # fn approx_equal(a: f32, b: f32) -> bool {
+    /// #     const DELTA: f32 = 0.001;
+    /// #     a > b - DELTA && a < b + DELTA
+    /// # }
+    /// ```
+    pub fn rotate_hue(&self, degrees: f32) -> Vector {
+        let (h, s, v) = rgb_to_hsv(self.x(), self.y(), self.z());
+        let (r, g, b) = hsv_to_rgb((h + degrees).rem_euclid(360.0), s, v);
+
+        let mut result = *self;
+        result.set_xyz(r, g, b);
+        result
+    }
+
+    /// Multiplies `x`, `y` and `z` by the values of other vector, keeping `w` of this vector.
+    ///
+    /// ```
+    /// use vec_2_10_10_10::Vector;
+    ///
+    /// let value = Vector::new(0.5, 1.0, 0.0, 1.0).modulate_rgb(&Vector::new(0.5, 0.25, 1.0, 0.0));
+    ///
+    /// assert!(approx_equal(value.x(), 0.25));
+    /// assert!(approx_equal(value.y(), 0.25));
+    /// assert!(approx_equal(value.z(), 0.0));
+    /// assert!(approx_equal(value.w(), 1.0));
+    /// #
+    /// # fn approx_equal(a: f32, b: f32) -> bool {
+    /// #     const DELTA: f32 = 0.001;
+    /// #     a > b - DELTA && a < b + DELTA
+    /// # }
+    /// ```
+    pub fn modulate_rgb(&self, other: &Vector) -> Vector {
+        let mut result = *self;
+        result.set_xyz(
+            self.x() * other.x(),
+            self.y() * other.y(),
+            self.z() * other.z(),
+        );
+        result
+    }
+
+    /// Multiplies `x`, `y` and `z` by `factor`, keeping `w`.
+    ///
+    /// The results are clamped to `0.0..=1.0`.
+    ///
+    /// ```
+    /// let value = vec_2_10_10_10::Vector::new(0.25, 0.5, 0.75, 1.0).scale_rgb(2.0);
+    ///
+    /// assert!(approx_equal(value.x(), 0.5));
+    /// assert!(approx_equal(value.y(), 1.0));
+    /// assert!(approx_equal(value.z(), 1.0));
+    /// assert!(approx_equal(value.w(), 1.0));
+    /// #
+    /// # fn approx_equal(a: f32, b: f32) -> bool {
+    /// #     const DELTA: f32 = 0.001;
+    /// #     a > b - DELTA && a < b + DELTA
+    /// # }
+    /// ```
+    pub fn scale_rgb(&self, factor: f32) -> Vector {
+        self.checked_scale_rgb(factor).0
+    }
+
+    /// Same as `scale_rgb`, but also returns `true` if any of `x`, `y` or `z` was
+    /// clipped at `1.0`.
+    ///
+    /// ```
+    /// let value = vec_2_10_10_10::Vector::new(0.25, 0.5, 0.75, 1.0);
+    ///
+    /// assert!(!value.checked_scale_rgb(1.25).1);
+    /// assert!(value.checked_scale_rgb(1.5).1);
+    /// ```
+    pub fn checked_scale_rgb(&self, factor: f32) -> (Vector, bool) {
+        let (x, y, z) = (self.x() * factor, self.y() * factor, self.z() * factor);
+
+        let mut result = *self;
+        result.set_xyz(x, y, z);
+        (result, x > 1.0 || y > 1.0 || z > 1.0)
+    }
+
+    /// Computes `c * gain + bias` for each of `x`, `y` and `z`, keeping `w`.
+    ///
+    /// The results are clamped to `0.0..=1.0`.
+    ///
+    /// ```
+    /// let value = vec_2_10_10_10::Vector::new(0.5, 0.5, 0.5, 1.0)
+    ///     .gain_bias([2.0, 1.0, 0.5], [-0.25, 0.1, 0.0]);
+    ///
+    /// assert!(approx_equal(value.x(), 0.75));
+    /// assert!(approx_equal(value.y(), 0.6));
+    /// assert!(approx_equal(value.z(), 0.25));
+    /// assert!(approx_equal(value.w(), 1.0));
+    /// #
+    /// # fn approx_equal(a: f32, b: f32) -> bool {
+    /// #     const DELTA: f32 = 0.001;
+    /// #     a > b - DELTA && a < b + DELTA
+    /// # }
+    /// ```
+    pub fn gain_bias(&self, gain: [f32; 3], bias: [f32; 3]) -> Vector {
+        let mut result = *self;
+        result.set_xyz(
+            self.x() * gain[0] + bias[0],
+            self.y() * gain[1] + bias[1],
+            self.z() * gain[2] + bias[2],
+        );
+        result
+    }
+
+    /// Raises each of `x`, `y` and `z` to the power of `exponent`, keeping `w`.
+    ///
+    /// The results are clamped to `0.0..=1.0`.
+    ///
+    /// ```
+    /// let value = vec_2_10_10_10::Vector::new(0.25, 1.0, 0.0, 0.333).gamma(0.5);
+    ///
+    /// assert!(approx_equal(value.x(), 0.5));
+    /// assert_eq!(value.y(), 1.0);
+    /// assert_eq!(value.z(), 0.0);
+    /// assert!(approx_equal(value.w(), 0.333));
+    /// #
+    /// # fn approx_equal(a: f32, b: f32) -> bool {
+    /// #     const DELTA: f32 = 0.001;
+    /// #     a > b - DELTA && a < b + DELTA
+    /// # }
+    /// ```
+    pub fn gamma(&self, exponent: f32) -> Vector {
+        let mut result = *self;
+        result.set_xyz(
+            self.x().powf(exponent),
+            self.y().powf(exponent),
+            self.z().powf(exponent),
+        );
+        result
+    }
+
+    /// Scales `x`, `y` and `z` down so that the largest of them is at most `max`, keeping `w`.
+    ///
+    /// All three values are multiplied by the same factor, so the ratios between them,
+    /// and therefore the hue, are preserved. Colors that are already dim enough are
+    /// returned unchanged. Unlike clamping every value separately, this does not shift
+    /// the hue of bright colors.
+    ///
+    /// ```
+    /// let value = vec_2_10_10_10::Vector::new(1.0, 0.5, 0.0, 0.333).clamp_brightness(0.8);
+    ///
+    /// assert!(approx_equal(value.x(), 0.8));
+    /// assert!(approx_equal(value.y(), 0.4));
+    /// assert_eq!(value.z(), 0.0);
+    /// assert!(approx_equal(value.w(), 0.333));
+    /// #
+    /// # fn approx_equal(a: f32, b: f32) -> bool {
+    /// #     const DELTA: f32 = 0.001;
+    /// #     a > b - DELTA && a < b + DELTA
+    /// # }
+    /// ```
+    pub fn clamp_brightness(&self, max: f32) -> Vector {
+        let brightest = self.x().max(self.y()).max(self.z());
+        if brightest <= max {
+            return *self;
+        }
+
+        let scale = max.max(0.0) / brightest;
+        let mut result = *self;
+        result.set_xyz(self.x() * scale, self.y() * scale, self.z() * scale);
+        result
+    }
+
+    /// Multiplies `x`, `y` and `z` by a 3x3 matrix, keeping `w`.
+    ///
+    /// The matrix is row-major, and the vector is a column on the right side:
+    /// each output channel `i` is `matrix[i][0] * x + matrix[i][1] * y + matrix[i][2] * z`.
+    /// The results are clamped to `0.0..=1.0`.
+    ///
+    /// ```
+    /// let value = vec_2_10_10_10::Vector::new(1.0, 0.5, 0.0, 1.0);
+    ///
+    /// // Swap x and z.
+    /// let swapped = value.transform_rgb([
+    ///     [0.0, 0.0, 1.0],
+    ///     [0.0, 1.0, 0.0],
+    ///     [1.0, 0.0, 0.0],
+    /// ]);
+    ///
+    /// assert_eq!(swapped.x(), value.z());
+    /// assert_eq!(swapped.y(), value.y());
+    /// assert_eq!(swapped.z(), value.x());
+    /// assert_eq!(swapped.w(), value.w());
+    /// ```
+    pub fn transform_rgb(&self, matrix: [[f32; 3]; 3]) -> Vector {
+        let (x, y, z) = (self.x(), self.y(), self.z());
+        let row = |r: [f32; 3]| r[0] * x + r[1] * y + r[2] * z;
+
+        let mut result = *self;
+        result.set_xyz(row(matrix[0]), row(matrix[1]), row(matrix[2]));
+        result
+    }
+
+    /// Interpolates three vectors with barycentric weights.
+    ///
+    /// Computes `a * u + b * v + c * (1.0 - u - v)` for every component, including `w`.
+    /// The results are clamped to `0.0..=1.0`. Note that interpolated `w` snaps to
+    /// one of its 4 levels, so it changes in coarse steps across a triangle.
+    ///
+    /// ```
+    /// use vec_2_10_10_10::Vector;
+    ///
+    /// let a = Vector::new(1.0, 0.0, 0.0, 1.0);
+    /// let b = Vector::new(0.0, 1.0, 0.0, 1.0);
+    /// let c = Vector::new(0.0, 0.0, 1.0, 0.0);
+    ///
+    /// assert_eq!(Vector::barycentric(&a, &b, &c, 1.0, 0.0).raw_value(), a.raw_value());
+    ///
+    /// let center = Vector::barycentric(&a, &b, &c, 1.0 / 3.0, 1.0 / 3.0);
+    /// assert!(approx_equal(center.x(), 0.333));
+    /// assert!(approx_equal(center.y(), 0.333));
+    /// assert!(approx_equal(center.z(), 0.333));
+    /// assert!(approx_equal(center.w(), 0.666));
+    /// #
+    /// # fn approx_equal(a: f32, b: f32) -> bool {
+    /// #     const DELTA: f32 = 0.001;
+    /// #     a > b - DELTA && a < b + DELTA
+    /// # }
+    /// ```
+    pub fn barycentric(a: &Vector, b: &Vector, c: &Vector, u: f32, v: f32) -> Vector {
+        let t = 1.0 - u - v;
+        Vector::new_clamped(
+            a.x() * u + b.x() * v + c.x() * t,
+            a.y() * u + b.y() * v + c.y() * t,
+            a.z() * u + b.z() * v + c.z() * t,
+            a.w() * u + b.w() * v + c.w() * t,
+        )
+    }
+
+    /// Linearly interpolate stored values towards the other vector.
+    ///
+    /// Computes `self + (other - self) * t` for every component, including `w`.
+    /// The results are clamped to `0.0..=1.0`.
+    ///
+    /// Use this for data that is not a color, or for colors stored as linear values.
+    /// For sRGB-encoded colors use `lerp_linear`.
+    ///
+    /// ```
+    /// use vec_2_10_10_10::Vector;
+    ///
+    /// let a = Vector::new(0.0, 0.2, 1.0, 0.0);
+    /// let b = Vector::new(1.0, 0.4, 0.0, 1.0);
+    /// let mid = a.lerp(&b, 0.5);
+    ///
+    /// assert!(approx_equal(mid.x(), 0.5));
+    /// assert!(approx_equal(mid.y(), 0.3));
+    /// assert!(approx_equal(mid.z(), 0.5));
+    /// assert!(approx_equal(mid.w(), 0.666));
+    /// #
+    /// # fn approx_equal(a: f32, b: f32) -> bool {
+    /// #     const DELTA: f32 = 0.001;
+    /// #     a > b - DELTA && a < b + DELTA
+    /// # }
+    /// ```
+    pub fn lerp(&self, other: &Vector, t: f32) -> Vector {
+        let a = self.to_normalized();
+        let b = other.to_normalized();
+        Vector::new_clamped(
+            a[0] + (b[0] - a[0]) * t,
+            a[1] + (b[1] - a[1]) * t,
+            a[2] + (b[2] - a[2]) * t,
+            a[3] + (b[3] - a[3]) * t,
+        )
+    }
+
+    /// Linearly interpolate sRGB-encoded color towards the other color in linear space.
+    ///
+    /// The `x`, `y` and `z` values are decoded from sRGB to linear, interpolated and
+    /// encoded back, which gives perceptually correct gradients. The `w` value is
+    /// interpolated as is. The results are clamped to `0.0..=1.0`.
+    ///
+    /// ```
+    /// use vec_2_10_10_10::Vector;
+    ///
+    /// let black = Vector::new(0.0, 0.0, 0.0, 1.0);
+    /// let white = Vector::new(1.0, 1.0, 1.0, 1.0);
+    /// let mid = black.lerp_linear(&white, 0.5);
+    ///
+    /// assert!(approx_equal(mid.x(), 0.735));
+    /// assert_eq!(mid.w(), 1.0);
+    /// #
+    /// # fn approx_equal(a: f32, b: f32) -> bool {
+    /// #     const DELTA: f32 = 0.001;
+    /// #     a > b - DELTA && a < b + DELTA
+    /// # }
+    /// ```
+    pub fn lerp_linear(&self, other: &Vector, t: f32) -> Vector {
+        let a = self.to_linear_srgb();
+        let b = other.to_linear_srgb();
+        Vector::from_linear_srgb(
+            a[0] + (b[0] - a[0]) * t,
+            a[1] + (b[1] - a[1]) * t,
+            a[2] + (b[2] - a[2]) * t,
+            a[3] + (b[3] - a[3]) * t,
+        )
+    }
+
+    /// Averages 4 vectors of a 2x2 tile, as a box filter for generating mip levels.
+    ///
+    /// Stored values are averaged as is, including `w`. Use this for data that is not
+    /// a color, or for colors stored as linear values. For sRGB-encoded colors use
+    /// `downsample_2x2_srgb`.
+    ///
+    /// ```
+    /// use vec_2_10_10_10::Vector;
+    ///
+    /// let black = Vector::new(0.0, 0.0, 0.0, 1.0);
+    /// let white = Vector::new(1.0, 1.0, 1.0, 0.0);
+    /// let mip = Vector::downsample_2x2(&black, &white, &white, &black);
+    ///
+    /// assert!(approx_equal(mip.x(), 0.5));
+    /// assert!(approx_equal(mip.w(), 0.666));
+    /// #
+    /// # fn approx_equal(a: f32, b: f32) -> bool {
+    /// #     const DELTA: f32 = 0.001;
+    /// #     a > b - DELTA && a < b + DELTA
+    /// # }
+    /// ```
+    pub fn downsample_2x2(tl: &Vector, tr: &Vector, bl: &Vector, br: &Vector) -> Vector {
+        let (a, b, c, d) = (tl.to_normalized(), tr.to_normalized(), bl.to_normalized(), br.to_normalized());
+        Vector::new_clamped(
+            (a[0] + b[0] + c[0] + d[0]) / 4.0,
+            (a[1] + b[1] + c[1] + d[1]) / 4.0,
+            (a[2] + b[2] + c[2] + d[2]) / 4.0,
+            (a[3] + b[3] + c[3] + d[3]) / 4.0,
+        )
+    }
+
+    /// Averages 4 sRGB-encoded colors of a 2x2 tile in linear space.
+    ///
+    /// The `x`, `y` and `z` values are decoded from sRGB to linear, averaged and encoded
+    /// back, which keeps the brightness of mip levels consistent. The `w` value is
+    /// averaged as is.
+    ///
+    /// ```
+    /// use vec_2_10_10_10::Vector;
+    ///
+    /// let black = Vector::new(0.0, 0.0, 0.0, 1.0);
+    /// let white = Vector::new(1.0, 1.0, 1.0, 1.0);
+    /// let mip = Vector::downsample_2x2_srgb(&black, &white, &white, &black);
+    ///
+    /// assert!(approx_equal(mip.x(), 0.735));
+    /// #
+    /// # fn approx_equal(a: f32, b: f32) -> bool {
+    /// #     const DELTA: f32 = 0.001;
+    /// #     a > b - DELTA && a < b + DELTA
+    /// # }
+    /// ```
+    pub fn downsample_2x2_srgb(tl: &Vector, tr: &Vector, bl: &Vector, br: &Vector) -> Vector {
+        let (a, b, c, d) = (tl.to_linear_srgb(), tr.to_linear_srgb(), bl.to_linear_srgb(), br.to_linear_srgb());
+        Vector::from_linear_srgb(
+            (a[0] + b[0] + c[0] + d[0]) / 4.0,
+            (a[1] + b[1] + c[1] + d[1]) / 4.0,
+            (a[2] + b[2] + c[2] + d[2]) / 4.0,
+            (a[3] + b[3] + c[3] + d[3]) / 4.0,
+        )
+    }
+
+    /// Returns whichever of the two vectors has larger `w` value, with all its values.
+    ///
+    /// If both have the same `w`, returns this vector.
+    ///
+    /// ```
+    /// use vec_2_10_10_10::Vector;
+    ///
+    /// let a = Vector::new(1.0, 0.0, 0.0, 0.333);
+    /// let b = Vector::new(0.0, 1.0, 0.0, 1.0);
+    ///
+    /// assert_eq!(a.max_alpha(&b).raw_value(), b.raw_value());
+    /// assert_eq!(a.min_alpha(&b).raw_value(), a.raw_value());
+    /// ```
+    pub fn max_alpha(&self, other: &Vector) -> Vector {
+        if other.alpha_level() > self.alpha_level() { *other } else { *self }
+    }
+
+    /// Returns whichever of the two vectors has smaller `w` value, with all its values.
+    ///
+    /// If both have the same `w`, returns this vector.
+    pub fn min_alpha(&self, other: &Vector) -> Vector {
+        if other.alpha_level() < self.alpha_level() { *other } else { *self }
+    }
+
+    /// Returns perceptual CIE76 color difference (ΔE*ab) between two vectors.
+    ///
+    /// The `x`, `y` and `z` values of both vectors are treated as sRGB, converted to
+    /// CIELAB with D65 white point, and the euclidean distance in Lab space is returned.
+    /// The `w` value is ignored. A difference of about `2.3` is just noticeable.
+    ///
+    /// ```
+    /// use vec_2_10_10_10::Vector;
+    ///
+    /// let black = Vector::new(0.0, 0.0, 0.0, 1.0);
+    /// let white = Vector::new(1.0, 1.0, 1.0, 1.0);
+    ///
+    /// assert!(approx_equal(black.delta_e(&white), 100.0));
+    /// assert_eq!(white.delta_e(&white), 0.0);
+    /// #
+    /// # fn approx_equal(a: f32, b: f32) -> bool {
+    /// #     const DELTA: f32 = 0.01;
+    /// #     a > b - DELTA && a < b + DELTA
+    /// # }
+    /// ```
+    pub fn delta_e(&self, other: &Vector) -> f32 {
+        let a = srgb_to_lab(self.x(), self.y(), self.z());
+        let b = srgb_to_lab(other.x(), other.y(), other.z());
+        ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt()
+    }
+
+    /// Returns index of the palette entry closest to this vector, or `None` if the palette is empty.
+    ///
+    /// The distance is squared Euclidean distance between raw `x`, `y` and `z` fields.
+    /// The `w` value is ignored. Ties resolve to the lowest index.
+    ///
+    /// ```
+    /// use vec_2_10_10_10::Vector;
+    ///
+    /// let palette = [Vector::new(0.0, 0.0, 0.0, 1.0), Vector::new(1.0, 0.0, 0.0, 1.0)];
+    ///
+    /// assert_eq!(Vector::new(0.8, 0.1, 0.0, 0.0).nearest_xyz(&palette), Some(1));
+    /// assert_eq!(Vector::new(0.2, 0.1, 0.0, 0.0).nearest_xyz(&palette), Some(0));
+    /// assert_eq!(Vector::new(0.2, 0.1, 0.0, 0.0).nearest_xyz(&[]), None);
+    /// ```
+    pub fn nearest_xyz(&self, palette: &[Vector]) -> Option<usize> {
+        let a = self.as_uvec4();
+        palette.iter()
+            .map(|entry| {
+                let b = entry.as_uvec4();
+                (0..3).map(|i| (a[i] as i64 - b[i] as i64).pow(2)).sum::<i64>()
+            })
+            .enumerate()
+            .min_by_key(|&(_, distance)| distance)
+            .map(|(i, _)| i)
+    }
+
+    /// Returns `true` if `w` value is greater than `threshold`.
+    ///
+    /// Since `w` can only be `0.0`, `0.3(3)`, `0.6(6)` or `1.0`, the result only changes when
+    /// `threshold` crosses one of these levels. For example, any threshold in `0.0..0.3(3)`
+    /// passes the same vectors.
+    ///
+    /// ```
+    /// let value = vec_2_10_10_10::Vector::new(0.0, 0.0, 0.0, 0.666);
+    ///
+    /// assert!(value.alpha_test(0.5));
+    /// assert!(value.alpha_test(0.6));
+    /// assert!(!value.alpha_test(0.7));
+    /// ```
+    pub fn alpha_test(&self, threshold: f32) -> bool {
+        self.w() > threshold
+    }
+
+    /// Returns alpha-to-coverage bitmask of covered samples out of `sample_count`.
+    ///
+    /// The number of covered samples is `w * sample_count`, rounded to the nearest integer.
+    /// Covered samples are spread evenly over the mask with a fixed pattern: sample `i` is
+    /// covered if `(i + 1) * covered / sample_count` is larger than `i * covered / sample_count`,
+    /// using integer division. The same vector always produces the same mask.
+    ///
+    /// Panics if `sample_count` is more than `32`.
+    ///
+    /// ```
+    /// use vec_2_10_10_10::Vector;
+    ///
+    /// assert_eq!(Vector::new(0.0, 0.0, 0.0, 1.0).coverage_mask(4), 0b1111);
+    /// assert_eq!(Vector::new(0.0, 0.0, 0.0, 0.666).coverage_mask(4), 0b1110);
+    /// assert_eq!(Vector::new(0.0, 0.0, 0.0, 0.666).coverage_mask(8), 0b1101_1010);
+    /// assert_eq!(Vector::new(0.0, 0.0, 0.0, 0.0).coverage_mask(8), 0);
+    /// ```
+    pub fn coverage_mask(&self, sample_count: u32) -> u32 {
+        assert!(sample_count <= 32, "sample count must be at most 32, got {}", sample_count);
+
+        let n = sample_count as u64;
+        let covered = (self.w() * sample_count as f32).round() as u64;
+        let mut mask = 0;
+        for i in 0..n {
+            if (i + 1) * covered / n > i * covered / n {
+                mask |= 1 << i;
+            }
+        }
+        mask
+    }
+
+    /// Returns index of the largest of `x`, `y` and `z` values (`0`, `1` or `2`).
+    ///
+    /// The `w` value is ignored. Ties resolve to the lowest index.
+    ///
+    /// ```
+    /// assert_eq!(vec_2_10_10_10::Vector::new(0.1, 0.8, 0.3, 1.0).dominant_channel(), 1);
+    /// assert_eq!(vec_2_10_10_10::Vector::new(0.1, 0.3, 0.3, 1.0).dominant_channel(), 1);
+    /// assert_eq!(vec_2_10_10_10::Vector::new(0.0, 0.0, 0.0, 1.0).dominant_channel(), 0);
+    /// ```
+    pub fn dominant_channel(&self) -> usize {
+        let fields = self.to_raw_fields();
+        let mut dominant = 0;
+        for i in 1..3 {
+            if fields[i] > fields[dominant] {
+                dominant = i;
+            }
+        }
+        dominant
+    }
+
+    /// Return raw internal value.
+    pub fn raw_value(&self) -> u32 {
+        self.data
+    }
+
+    /// Copy out raw internal value.
+    ///
+    /// Same as `raw_value`. Because the struct is packed, the value is always returned
+    /// by copy, never by reference.
+    pub fn copy_raw(&self) -> u32 {
+        self.data
+    }
+
+    /// Return raw internal value converted to little-endian byte order.
+    ///
+    /// Writing the result in native byte order produces little-endian bytes.
+    /// On little-endian platforms this is the same as `raw_value`.
+    ///
+    /// ```
+    /// let value = vec_2_10_10_10::Vector::from_raw(0x1122_3344);
+    ///
+    /// assert_eq!(value.raw_value_le().to_ne_bytes(), [0x44, 0x33, 0x22, 0x11]);
+    /// assert_eq!(value.raw_value_be().to_ne_bytes(), [0x11, 0x22, 0x33, 0x44]);
+    /// ```
+    pub fn raw_value_le(&self) -> u32 {
+        self.data.to_le()
+    }
+
+    /// Return raw internal value converted to big-endian byte order.
+    ///
+    /// Writing the result in native byte order produces big-endian bytes.
+    /// On big-endian platforms this is the same as `raw_value`.
+    pub fn raw_value_be(&self) -> u32 {
+        self.data.to_be()
+    }
+
+    /// Return raw internal value with the `w` field masked out.
+    ///
+    /// Useful as a key for grouping colors that share `x`, `y` and `z`.
+    ///
+    /// ```
+    /// let value = vec_2_10_10_10::Vector::from_raw(0xffff_ffff);
+    /// assert_eq!(value.rgb_raw(), 0x3fff_ffff);
+    /// ```
+    pub fn rgb_raw(&self) -> u32 {
+        self.data & 0x3fff_ffff
+    }
+
+    /// Checks if both vectors have the same raw `x`, `y` and `z`, ignoring `w`.
+    ///
+    /// ```
+    /// use vec_2_10_10_10::Vector;
+    ///
+    /// let a = Vector::new(0.2, 0.4, 0.6, 1.0);
+    /// assert!(a.same_rgb(&Vector::new(0.2, 0.4, 0.6, 0.0)));
+    /// assert!(!a.same_rgb(&Vector::new(0.2, 0.4, 0.7, 1.0)));
+    /// ```
+    pub fn same_rgb(&self, other: &Vector) -> bool {
+        self.rgb_raw() == other.rgb_raw()
+    }
+
+    /// Compares raw `x`, `y` and `z` of both vectors, ignoring `w`.
+    ///
+    /// Same as `same_rgb`, named to pair with `cmp_rgb`.
+    pub fn eq_rgb(&self, other: &Vector) -> bool {
+        self.same_rgb(other)
+    }
+
+    /// Orders vectors by `rgb_raw`, ignoring `w`.
+    ///
+    /// Vectors are ordered by raw `z` first, then `y`, then `x`, because that is
+    /// the order of the fields from the highest bits of the raw value.
+    ///
+    /// ```
+    /// use std::cmp::Ordering;
+    /// use vec_2_10_10_10::Vector;
+    ///
+    /// let a = Vector::new(0.2, 0.4, 0.6, 1.0);
+    /// let b = Vector::new(0.2, 0.4, 0.6, 0.0);
+    /// let c = Vector::new(0.0, 0.0, 0.7, 0.0);
+    ///
+    /// assert!(a.eq_rgb(&b));
+    /// assert_eq!(a.cmp_rgb(&b), Ordering::Equal);
+    /// assert_eq!(a.cmp_rgb(&c), Ordering::Less);
+    ///
+    /// let mut colors = vec![c, a, b];
+    /// colors.sort_by(Vector::cmp_rgb);
+    /// assert!(colors[2].eq_rgb(&c));
+    /// ```
+    pub fn cmp_rgb(&self, other: &Vector) -> cmp::Ordering {
+        self.rgb_raw().cmp(&other.rgb_raw())
+    }
+
+    /// Checks that decoded values are in `0.0..=1.0` range, and that packing them
+    /// again produces the same raw value.
+    ///
+    /// This should hold for every raw value, and is intended for fuzz tests.
+    ///
+    /// ```
+    /// assert!(vec_2_10_10_10::Vector::from_raw(0xdead_beef).check_invariants());
+    /// ```
+    pub fn check_invariants(&self) -> bool {
+        let values = self.to_normalized();
+        values.iter().all(|v| (0.0..=1.0).contains(v))
+            && Vector::closest_array(&values).data == self.data
+    }
+
+    /// Returns the vector decoded and packed again, which is the canonical bit pattern of its values.
+    ///
+    /// In this format every bit pattern is canonical, so this always returns the same
+    /// raw value. It exists so that tooling can rely on it regardless of the layout.
+    ///
+    /// ```
+    /// let value = vec_2_10_10_10::Vector::from_raw(0xdead_beef);
+    ///
+    /// assert!(value.is_canonical());
+    /// assert_eq!(value.canonicalize().raw_value(), 0xdead_beef);
+    /// ```
+    pub fn canonicalize(&self) -> Vector {
+        Vector::closest_array(&self.to_normalized())
+    }
+
+    /// Checks if the raw value is the canonical bit pattern of its values.
+    ///
+    /// See `canonicalize`.
+    pub fn is_canonical(&self) -> bool {
+        self.canonicalize().data == self.data
+    }
+
+    /// Return number of set bits in raw internal value.
+    ///
+    /// ```
+    /// assert_eq!(vec_2_10_10_10::Vector::new(0.0, 0.0, 0.0, 0.0).raw_popcount(), 0);
+    /// assert_eq!(vec_2_10_10_10::Vector::new(1.0, 1.0, 1.0, 1.0).raw_popcount(), 32);
+    /// assert_eq!(vec_2_10_10_10::Vector::new(0.0, 0.0, 0.0, 1.0).raw_popcount(), 2);
+    /// ```
+    pub fn raw_popcount(&self) -> u32 {
+        self.data.count_ones()
+    }
+
+    /// Return XOR of raw internal values of this and previous vector.
+    ///
+    /// Deltas of similar vectors have many zero bits, which compress well.
+    /// Use `apply_raw_delta` to restore the vector.
+    ///
+    /// ```
+    /// use vec_2_10_10_10::Vector;
+    ///
+    /// let prev = Vector::new(0.444, 0.555, 0.666, 1.0);
+    /// let next = Vector::new(0.445, 0.555, 0.666, 1.0);
+    ///
+    /// let delta = next.raw_delta(&prev);
+    /// assert_eq!(delta >> 10, 0);
+    /// assert_eq!(Vector::apply_raw_delta(&prev, delta).raw_value(), next.raw_value());
+    /// ```
+    pub fn raw_delta(&self, prev: &Vector) -> u32 {
+        self.data ^ prev.data
+    }
+
+    /// Checks that every raw field differs from the other vector by at most the given
+    /// number of quantization steps, in `[x, y, z, w]` order.
+    ///
+    /// ```
+    /// use vec_2_10_10_10::Vector;
+    ///
+    /// let a = Vector::from_raw_fields([100, 200, 300, 1]);
+    /// let b = Vector::from_raw_fields([101, 200, 298, 2]);
+    ///
+    /// assert!(a.within_steps(&b, [1, 0, 2, 1]));
+    /// assert!(!a.within_steps(&b, [1, 0, 1, 1]));
+    /// ```
+    pub fn within_steps(&self, other: &Vector, steps: [u32; 4]) -> bool {
+        let a = self.to_raw_fields();
+        let b = other.to_raw_fields();
+        (0..4).all(|i| (a[i] as i32 - b[i] as i32).unsigned_abs() <= steps[i])
+    }
+
+    /// Checks that decoded `x`, `y` and `z` differ by at most `rgb_eps`, and `w` by at most `alpha_eps`.
+    ///
+    /// Separate tolerances match the precision of the fields: one step of `x`, `y` and `z`
+    /// is `1/1023`, while one step of `w` is `1/3`.
+    ///
+    /// ```
+    /// use vec_2_10_10_10::Vector;
+    ///
+    /// let a = Vector::new(0.5, 0.5, 0.5, 0.333);
+    /// let b = Vector::new(0.501, 0.5, 0.5, 0.666);
+    ///
+    /// assert!(a.approx_eq_split(&b, 0.002, 0.34));
+    /// assert!(!a.approx_eq_split(&b, 0.002, 0.1));
+    /// assert!(!a.approx_eq_split(&b, 0.0005, 0.34));
+    /// ```
+    pub fn approx_eq_split(&self, other: &Vector, rgb_eps: f32, alpha_eps: f32) -> bool {
+        let a = self.to_normalized();
+        let b = other.to_normalized();
+        (0..3).all(|i| (a[i] - b[i]).abs() <= rgb_eps) && (a[3] - b[3]).abs() <= alpha_eps
+    }
+
+    /// Returns `self - other` for every decoded value, in `[x, y, z, w]` order.
+    ///
+    /// The differences are in `-1.0..=1.0` range, and keep the direction of the change.
+    ///
+    /// ```
+    /// use vec_2_10_10_10::Vector;
+    ///
+    /// let a = Vector::new(1.0, 0.25, 0.5, 0.0);
+    /// let b = Vector::new(0.0, 0.5, 0.5, 1.0);
+    /// let delta = a.signed_delta(&b);
+    ///
+    /// assert_eq!(delta[0], 1.0);
+    /// assert!(approx_equal(delta[1], -0.25));
+    /// assert_eq!(delta[2], 0.0);
+    /// assert_eq!(delta[3], -1.0);
+    /// #
+    /// # fn approx_equal(a: f32, b: f32) -> bool {
+    /// #     const DELTA: f32 = 0.001;
+    /// #     a > b - DELTA && a < b + DELTA
+    /// # }
+    /// ```
+    pub fn signed_delta(&self, other: &Vector) -> [f32; 4] {
+        let a = self.to_normalized();
+        let b = other.to_normalized();
+        [a[0] - b[0], a[1] - b[1], a[2] - b[2], a[3] - b[3]]
+    }
+
+    /// Describes which raw fields differ from the other vector, one line per field.
+    ///
+    /// Intended for debugging mismatches in tests.
+    ///
+    /// ```
+    /// use vec_2_10_10_10::Vector;
+    ///
+    /// let a = Vector::from_raw_fields([512, 200, 300, 1]);
+    /// let b = Vector::from_raw_fields([513, 200, 298, 1]);
+    ///
+    /// assert_eq!(a.explain_diff(&b), "x: 512 vs 513 (+1)\ny: same\nz: 300 vs 298 (-2)\nw: same");
+    /// ```
+    pub fn explain_diff(&self, other: &Vector) -> String {
+        let a = self.to_raw_fields();
+        let b = other.to_raw_fields();
+
+        ["x", "y", "z", "w"].iter().enumerate()
+            .map(|(i, name)| if a[i] == b[i] {
+                format!("{}: same", name)
+            } else {
+                format!("{}: {} vs {} ({:+})", name, a[i], b[i], b[i] as i32 - a[i] as i32)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Restores a vector from previous vector and a delta returned by `raw_delta`.
+    pub fn apply_raw_delta(prev: &Vector, delta: u32) -> Vector {
+        Vector {
+            data: prev.data ^ delta
+        }
+    }
+}
+
+/// Returns average number of set bits in raw values of all vectors.
+///
+/// Returns `0.0` for an empty slice.
+///
+/// ```
+/// use vec_2_10_10_10::Vector;
+///
+/// let vectors = [Vector::new(1.0, 1.0, 1.0, 1.0), Vector::new(0.0, 0.0, 0.0, 0.0)];
+/// assert_eq!(vec_2_10_10_10::average_popcount(&vectors), 16.0);
+/// assert_eq!(vec_2_10_10_10::average_popcount(&[]), 0.0);
+/// ```
+pub fn average_popcount(vectors: &[Vector]) -> f32 {
+    if vectors.is_empty() {
+        return 0.0;
+    }
+
+    let total: u64 = vectors.iter().map(|v| v.raw_popcount() as u64).sum();
+    (total as f64 / vectors.len() as f64) as f32
+}
+
+/// Resolves weighted blended order-independent transparency for the entries.
+///
+/// The resulting color is the sum of `rgb * a` divided by the sum of `a`. The resulting
+/// `w` is total coverage `1 - (1 - a0) * (1 - a1) * ...`.
+///
+/// Returns `Vector::TRANSPARENT_BLACK` if all entries have zero alpha or there are no entries.
+///
+/// Note that `w` has only 4 levels, so the weights are very coarse: each entry
+/// contributes with weight `0`, `1/3`, `2/3` or `1`.
+///
+/// ```
+/// use vec_2_10_10_10::Vector;
+///
+/// let entries = [Vector::new(1.0, 0.0, 0.0, 1.0), Vector::new(0.0, 0.0, 1.0, 0.333)];
+/// let resolved = vec_2_10_10_10::accumulate_oit(&entries);
+///
+/// assert!(approx_equal(resolved.x(), 0.75));
+/// assert!(approx_equal(resolved.z(), 0.25));
+/// assert_eq!(resolved.w(), 1.0);
+///
+/// let empty = vec_2_10_10_10::accumulate_oit(&[Vector::new(1.0, 1.0, 1.0, 0.0)]);
+/// assert_eq!(empty.raw_value(), Vector::TRANSPARENT_BLACK.raw_value());
+/// #
+/// # fn approx_equal(a: f32, b: f32) -> bool {
+/// #     const DELTA: f32 = 0.001;
+/// #     a > b - DELTA && a < b + DELTA
+/// # }
+/// ```
+pub fn accumulate_oit(entries: &[Vector]) -> Vector {
+    let mut sum = [0.0f32; 3];
+    let mut weight = 0.0f32;
+    let mut revealage = 1.0f32;
+
+    for entry in entries {
+        let a = entry.w();
+        sum[0] += entry.x() * a;
+        sum[1] += entry.y() * a;
+        sum[2] += entry.z() * a;
+        weight += a;
+        revealage *= 1.0 - a;
+    }
+
+    if weight == 0.0 {
+        return Vector::TRANSPARENT_BLACK;
+    }
+
+    Vector::new_clamped(sum[0] / weight, sum[1] / weight, sum[2] / weight, 1.0 - revealage)
+}
+
+/// Quantizes a `0.0..=1.0` value to an integer of the given bit depth.
+///
+/// The value is clamped to `0.0..=1.0`, multiplied by `2^bits - 1` and rounded to the
+/// nearest integer. This is exactly the logic `Vector` uses for every dimension, and can
+/// be reused for other bit layouts.
+///
+/// Panics if `bits` is `0` or more than `32`.
+///
+/// ```
+/// assert_eq!(vec_2_10_10_10::quantize(0.5, 10), 512);
+/// assert_eq!(vec_2_10_10_10::quantize(0.2, 2), 1);
+/// assert_eq!(vec_2_10_10_10::quantize(1.5, 8), 255);
+/// assert_eq!(vec_2_10_10_10::quantize(-1.0, 8), 0);
+/// assert_eq!(vec_2_10_10_10::quantize(1.0, 25), (1 << 25) - 1);
+/// assert_eq!(vec_2_10_10_10::quantize(1.0, 32), u32::MAX);
+/// ```
+#[inline]
+pub fn quantize(value: f32, bits: u8) -> u32 {
+    assert!(bits > 0 && bits <= 32, "bit depth must be in 1..=32, got {}", bits);
+    quantize_to(value, ((1u64 << bits) - 1) as u32)
+}
+
+/// Packs values following the unsigned normalized conversion of the OpenGL specification.
+///
+/// Every value is clamped to `0.0..=1.0`, multiplied by `2^bits - 1` and rounded to the
+/// nearest integer, with ties rounded to even. NaN is stored as `0`. This is independent
+/// of the code used by `Vector`, so that the two can be compared.
+///
+/// `Vector::new` rounds ties away from zero, so the results differ by one step for values
+/// that land exactly halfway between two levels.
+///
+/// ```
+/// use vec_2_10_10_10::Vector;
+///
+/// let reference = vec_2_10_10_10::gl_reference_pack(0.444, 0.555, 0.666, 0.333);
+/// assert_eq!(Vector::new(0.444, 0.555, 0.666, 0.333).raw_value(), reference);
+///
+/// // 2.5 steps of `x`, exactly halfway.
+/// let x = 2.5 / 1023.0;
+/// assert_eq!(vec_2_10_10_10::gl_reference_pack(x, 0.0, 0.0, 0.0), 2);
+/// assert_eq!(Vector::new(x, 0.0, 0.0, 0.0).raw_value(), 3);
+/// ```
+pub fn gl_reference_pack(x: f32, y: f32, z: f32, w: f32) -> u32 {
+    fn unorm(value: f32, bits: u32) -> u32 {
+        let max = ((1u32 << bits) - 1) as f32;
+        let value = if value.is_nan() { 0.0 } else { value.clamp(0.0, 1.0) };
+        (value * max).round_ties_even() as u32
+    }
+
+    unorm(w, 2) << 30 | unorm(z, 10) << 20 | unorm(y, 10) << 10 | unorm(x, 10)
+}
+
+/// Returns the signed difference between the values and what a `Vector` would store for them.
+///
+/// For every value this is `input - stored`, where `stored` is the value returned by the
+/// getter after packing with `Vector::closest`. The residual includes clamping, so it can
+/// be spread to neighbouring pixels in error-diffusion dithering.
+///
+/// ```
+/// let error = vec_2_10_10_10::quantization_error(0.5, 1.5, 0.0, 0.5);
+///
+/// assert!(approx_equal(error[0], 0.5 - 512.0 / 1023.0));
+/// assert!(approx_equal(error[1], 0.5));
+/// assert_eq!(error[2], 0.0);
+/// assert!(approx_equal(error[3], 0.5 - 2.0 / 3.0));
+/// #
+/// # fn approx_equal(a: f32, b: f32) -> bool {
+/// #     const DELTA: f32 = 0.0001;
+/// #     a > b - DELTA && a < b + DELTA
+/// # }
+/// ```
+pub fn quantization_error(x: f32, y: f32, z: f32, w: f32) -> [f32; 4] {
+    let stored = Vector::closest(x, y, z, w);
+    [x - stored.x(), y - stored.y(), z - stored.z(), w - stored.w()]
+}
+
+/// Returns the value that the getter would return after storing `value` in the given channel.
+///
+/// Channels `0`, `1` and `2` are the 10-bit `x`, `y` and `z`, and channel `3` is the 2-bit `w`.
+/// The value is clamped and rounded exactly like in `Vector::closest`.
+///
+/// Panics if `channel` is more than `3`.
+///
+/// ```
+/// assert!(approx_equal(vec_2_10_10_10::quantize_channel(0.35, 3), 0.333));
+/// assert!(approx_equal(vec_2_10_10_10::quantize_channel(0.35, 0), 0.35));
+/// assert_eq!(vec_2_10_10_10::quantize_channel(1.5, 1), 1.0);
+/// #
+/// # fn approx_equal(a: f32, b: f32) -> bool {
+/// #     const DELTA: f32 = 0.001;
+/// #     a > b - DELTA && a < b + DELTA
+/// # }
+/// ```
+pub fn quantize_channel(value: f32, channel: usize) -> f32 {
+    assert!(channel < 4, "channel must be in 0..=3, got {}", channel);
+    let mut values = [0.0; 4];
+    values[channel] = value;
+    Vector::closest_array(&values).to_normalized()[channel]
+}
+
+/// Packs and decodes every `[x, y, z, w]` input, and checks that no value changed by more than `max_error`.
+///
+/// On failure, returns the index of the first failing input and its error as returned
+/// by `quantization_error`.
+///
+/// ```
+/// let src = [[0.0, 0.5, 1.0, 1.0], [0.25, 0.5, 0.75, 0.5]];
+///
+/// assert_eq!(vec_2_10_10_10::verify_roundtrip(&src[..1], 0.001), Ok(()));
+///
+/// let (index, error) = vec_2_10_10_10::verify_roundtrip(&src, 0.001).unwrap_err();
+/// assert_eq!(index, 1);
+/// assert!(error[3].abs() > 0.1);
+/// ```
+pub fn verify_roundtrip(src: &[[f32; 4]], max_error: f32) -> Result<(), (usize, [f32; 4])> {
+    for (i, s) in src.iter().enumerate() {
+        let error = quantization_error(s[0], s[1], s[2], s[3]);
+        if error.iter().any(|e| e.abs() > max_error) {
+            return Err((i, error));
+        }
+    }
+    Ok(())
+}
+
+/// Computes peak signal-to-noise ratio in decibels per channel between reference values and packed vectors.
+///
+/// The peak value is `1.0`, so for every channel this is `10 * log10(1 / mse)`, where `mse`
+/// is the mean squared difference between reference values and decoded values.
+/// Higher is better. Returns `f32::INFINITY` for channels without any error, including
+/// all channels of empty slices.
+///
+/// Panics if slice lengths do not match.
+///
+/// ```
+/// use vec_2_10_10_10::Vector;
+///
+/// let reference = [[0.0, 0.5, 1.0, 0.5]];
+/// let packed = [Vector::closest(0.0, 0.5, 1.0, 0.5)];
+/// let psnr = vec_2_10_10_10::psnr(&reference, &packed);
+///
+/// assert_eq!(psnr[0], f32::INFINITY);
+/// assert!(psnr[1] > 60.0);
+/// assert!(psnr[3] < 20.0);
+/// ```
+pub fn psnr(reference: &[[f32; 4]], packed: &[Vector]) -> [f32; 4] {
+    assert_eq!(reference.len(), packed.len(), "slice lengths must match");
+
+    let mut sum = [0.0f64; 4];
+    for (r, p) in reference.iter().zip(packed) {
+        let decoded = p.to_normalized();
+        for i in 0..4 {
+            let d = (r[i] - decoded[i]) as f64;
+            sum[i] += d * d;
+        }
+    }
+
+    let mut result = [f32::INFINITY; 4];
+    for i in 0..4 {
+        if sum[i] > 0.0 {
+            let mse = sum[i] / reference.len() as f64;
+            result[i] = (10.0 * (1.0 / mse).log10()) as f32;
+        }
+    }
+    result
+}
+
+/// Checks if the values can be stored in a `Vector` without any rounding or clamping.
+///
+/// A value is exact if it lies on the quantization grid of its dimension: a multiple of
+/// `1/1023` for `x`, `y` and `z`, and a multiple of `1/3` for `w`. In other words, the
+/// getters return exactly the same values that were passed to `Vector::new`.
+///
+/// ```
+/// assert!(vec_2_10_10_10::is_exact(0.0, 1.0, 512.0 / 1023.0, 1.0 / 3.0));
+/// assert!(!vec_2_10_10_10::is_exact(0.5, 1.0, 0.0, 1.0));
+/// assert!(!vec_2_10_10_10::is_exact(0.0, 1.0, 0.0, 0.5));
+/// assert!(!vec_2_10_10_10::is_exact(0.0, 1.5, 0.0, 1.0));
+/// ```
+pub fn is_exact(x: f32, y: f32, z: f32, w: f32) -> bool {
+    fn on_grid(value: f32, steps: f32) -> bool {
+        clamp(value) == value && (value * steps).round() / steps == value
+    }
+
+    on_grid(x, 1023f32) && on_grid(y, 1023f32) && on_grid(z, 1023f32) && on_grid(w, 3f32)
+}
+
+/// Checks if 8-bit color can be stored in a `Vector` and converted back to the same 8-bit color.
+///
+/// Every one of 256 levels of `r`, `g` and `b` maps to a distinct 10-bit level, so only
+/// alpha decides: it must be `0`, `85`, `170` or `255`, which are the levels of 2-bit `w`.
+///
+/// ```
+/// assert!(vec_2_10_10_10::can_represent_u8_exactly([12, 200, 255, 170]));
+/// assert!(!vec_2_10_10_10::can_represent_u8_exactly([12, 200, 255, 128]));
+/// ```
+pub fn can_represent_u8_exactly(rgba: [u8; 4]) -> bool {
+    matches!(rgba[3], 0 | 85 | 170 | 255)
+}
+
+/// Returns per-component minimum and maximum of all vectors, or `None` if the slice is empty.
+///
+/// ```
+/// use vec_2_10_10_10::Vector;
+///
+/// let vectors = [Vector::new(0.0, 0.5, 1.0, 1.0), Vector::new(1.0, 0.25, 0.0, 0.0)];
+///
+/// let (min, max) = vec_2_10_10_10::component_bounds(&vectors).unwrap();
+/// assert_eq!(min, [0.0, vectors[1].y(), 0.0, 0.0]);
+/// assert_eq!(max, [1.0, vectors[0].y(), 1.0, 1.0]);
+///
+/// assert!(vec_2_10_10_10::component_bounds(&[]).is_none());
+/// ```
+pub fn component_bounds(vectors: &[Vector]) -> Option<([f32; 4], [f32; 4])> {
+    if vectors.is_empty() {
+        return None;
+    }
+
+    let mut min = [1f32; 4];
+    let mut max = [0f32; 4];
+    let mut values = [0f32; 4];
+
+    for vector in vectors {
+        vector.decode_into(&mut values);
+        for i in 0..4 {
+            min[i] = min[i].min(values[i]);
+            max[i] = max[i].max(values[i]);
+        }
+    }
+
+    Some((min, max))
+}
+
+/// Packs `[x, y, z, w]` values into vectors.
+///
+/// Each element is packed with `Vector::new`.
+///
+/// Panics if slice lengths do not match.
+///
+/// ```
+/// use vec_2_10_10_10::Vector;
+///
+/// let mut dst = [Vector::from_raw(0); 2];
+/// vec_2_10_10_10::encode_slice(&[[0.0, 0.5, 1.0, 0.0], [1.0, 0.5, 0.0, 1.0]], &mut dst);
+///
+/// assert_eq!(dst[0].raw_value(), Vector::new(0.0, 0.5, 1.0, 0.0).raw_value());
+/// assert_eq!(dst[1].raw_value(), Vector::new(1.0, 0.5, 0.0, 1.0).raw_value());
+/// ```
+pub fn encode_slice(src: &[[f32; 4]], dst: &mut [Vector]) {
+    assert_eq!(src.len(), dst.len(), "slice lengths must match");
+
+    for (vector, v) in dst.iter_mut().zip(src) {
+        *vector = Vector::new(v[0], v[1], v[2], v[3]);
+    }
+}
+
+/// Same as `encode_slice`, but packs chunks of the slice in parallel, using `rayon`.
+///
+/// Requires `rayon` feature.
+///
+/// Panics if slice lengths do not match.
+///
+/// ```
+/// use vec_2_10_10_10::Vector;
+///
+/// let src: Vec<[f32; 4]> = (0..10000).map(|i| [(i % 1024) as f32 / 1023.0, 0.5, 0.25, 1.0]).collect();
+/// let mut expected = vec![Vector::from_raw(0); src.len()];
+/// let mut dst = vec![Vector::from_raw(0); src.len()];
+///
+/// vec_2_10_10_10::encode_slice(&src, &mut expected);
+/// vec_2_10_10_10::encode_slice_par(&src, &mut dst);
+///
+/// assert_eq!(vec_2_10_10_10::diff_count(&expected, &dst), 0);
+/// ```
+#[cfg(feature = "rayon")]
+pub fn encode_slice_par(src: &[[f32; 4]], dst: &mut [Vector]) {
+    use rayon::prelude::*;
+
+    const CHUNK_SIZE: usize = 4096;
+
+    assert_eq!(src.len(), dst.len(), "slice lengths must match");
+
+    dst.par_chunks_mut(CHUNK_SIZE)
+        .zip(src.par_chunks(CHUNK_SIZE))
+        .for_each(|(dst, src)| encode_slice(src, dst));
+}
+
+/// Same as `encode_slice`, but first scales `x`, `y` and `z` of all values so that bright values fit into `0.0..=1.0`.
+///
+/// Brightness of a value is the largest of its `x`, `y` and `z`. The scale is chosen so
+/// that the 99th percentile brightness (nearest rank) becomes `1.0`: the brightest 1% of
+/// values are clamped, which keeps a few outliers from darkening the whole buffer.
+/// The `w` values are not scaled. If the percentile brightness is not positive, the
+/// scale is `1.0`.
+///
+/// Returns the applied scale. Divide decoded `x`, `y` and `z` by it to undo the exposure.
+///
+/// Panics if slice lengths do not match.
+///
+/// ```
+/// use vec_2_10_10_10::Vector;
+///
+/// let src = [[4.0, 2.0, 0.0, 1.0], [1.0, 1.0, 1.0, 0.0]];
+/// let mut dst = [Vector::from_raw(0); 2];
+/// let scale = vec_2_10_10_10::encode_auto_exposed(&src, &mut dst);
+///
+/// assert_eq!(scale, 0.25);
+/// assert_eq!(dst[0].raw_value(), Vector::new(1.0, 0.5, 0.0, 1.0).raw_value());
+/// assert_eq!(dst[1].raw_value(), Vector::new(0.25, 0.25, 0.25, 0.0).raw_value());
+/// ```
+pub fn encode_auto_exposed(src: &[[f32; 4]], dst: &mut [Vector]) -> f32 {
+    assert_eq!(src.len(), dst.len(), "slice lengths must match");
+
+    let mut brightness: Vec<f32> = src.iter().map(|v| v[0].max(v[1]).max(v[2])).collect();
+    brightness.sort_by(f32::total_cmp);
+
+    let scale = match brightness.len() {
+        0 => 1.0,
+        len => {
+            let rank = (len as f64 * 0.99).ceil() as usize;
+            let peak = brightness[rank.max(1) - 1];
+            if peak > 0.0 { 1.0 / peak } else { 1.0 }
+        }
+    };
+
+    for (vector, v) in dst.iter_mut().zip(src) {
+        *vector = Vector::new_clamped(v[0] * scale, v[1] * scale, v[2] * scale, v[3]);
+    }
+    scale
+}
+
+/// Lazily packs `[x, y, z, w]` values into vectors.
+///
+/// Each element is packed with `Vector::new`.
+///
+/// ```
+/// use vec_2_10_10_10::Vector;
+///
+/// let colors = [[0.0, 0.5, 1.0, 0.0], [1.0, 0.5, 0.0, 1.0]];
+/// let packed: Vec<Vector> = vec_2_10_10_10::pack_iter(colors.iter().copied()).collect();
+///
+/// assert_eq!(packed[1].raw_value(), Vector::new(1.0, 0.5, 0.0, 1.0).raw_value());
+/// ```
+pub fn pack_iter<I: IntoIterator<Item = [f32; 4]>>(iter: I) -> impl Iterator<Item = Vector> {
+    iter.into_iter().map(|v| Vector::new(v[0], v[1], v[2], v[3]))
+}
+
+/// Packs separate `x`, `y`, `z` and `w` slices (structure-of-arrays) into vectors.
+///
+/// Each element is packed with `Vector::new`.
+///
+/// Panics if slice lengths do not match.
+///
+/// ```
+/// use vec_2_10_10_10::Vector;
+///
+/// let mut dst = [Vector::from_raw(0); 2];
+/// vec_2_10_10_10::encode_soa(&[0.0, 1.0], &[0.5, 0.5], &[1.0, 0.0], &[0.0, 1.0], &mut dst);
+///
+/// assert_eq!(dst[0].raw_value(), Vector::new(0.0, 0.5, 1.0, 0.0).raw_value());
+/// assert_eq!(dst[1].raw_value(), Vector::new(1.0, 0.5, 0.0, 1.0).raw_value());
+/// ```
+pub fn encode_soa(xs: &[f32], ys: &[f32], zs: &[f32], ws: &[f32], dst: &mut [Vector]) {
+    let len = dst.len();
+    assert!(
+        xs.len() == len && ys.len() == len && zs.len() == len && ws.len() == len,
+        "slice lengths must match: xs {}, ys {}, zs {}, ws {}, dst {}",
+        xs.len(), ys.len(), zs.len(), ws.len(), len
+    );
+
+    for (i, vector) in dst.iter_mut().enumerate() {
+        *vector = Vector::new(xs[i], ys[i], zs[i], ws[i]);
+    }
+}
+
+/// Unpacks vectors into separate `x`, `y`, `z` and `w` slices (structure-of-arrays).
+///
+/// Panics if slice lengths do not match.
+///
+/// ```
+/// use vec_2_10_10_10::Vector;
+///
+/// let src = [Vector::new(0.0, 0.5, 1.0, 0.0), Vector::new(1.0, 0.5, 0.0, 1.0)];
+/// let (mut xs, mut ys, mut zs, mut ws) = ([0.0; 2], [0.0; 2], [0.0; 2], [0.0; 2]);
+/// vec_2_10_10_10::decode_soa(&src, &mut xs, &mut ys, &mut zs, &mut ws);
+///
+/// assert_eq!(xs, [0.0, 1.0]);
+/// assert_eq!(ys, [src[0].y(), src[1].y()]);
+/// assert_eq!(zs, [1.0, 0.0]);
+/// assert_eq!(ws, [0.0, 1.0]);
+/// ```
+pub fn decode_soa(src: &[Vector], xs: &mut [f32], ys: &mut [f32], zs: &mut [f32], ws: &mut [f32]) {
+    let len = src.len();
+    assert!(
+        xs.len() == len && ys.len() == len && zs.len() == len && ws.len() == len,
+        "slice lengths must match: src {}, xs {}, ys {}, zs {}, ws {}",
+        len, xs.len(), ys.len(), zs.len(), ws.len()
+    );
+
+    for (i, vector) in src.iter().enumerate() {
+        xs[i] = vector.x();
+        ys[i] = vector.y();
+        zs[i] = vector.z();
+        ws[i] = vector.w();
+    }
+}
+
+/// Applies `Vector::gamma` to every vector in place.
+///
+/// ```
+/// use vec_2_10_10_10::Vector;
+///
+/// let mut vectors = [Vector::new(0.25, 0.25, 0.25, 1.0), Vector::new(1.0, 0.0, 0.0, 0.0)];
+/// vec_2_10_10_10::apply_gamma_in_place(&mut vectors, 0.5);
+///
+/// assert_eq!(vectors[0].raw_value(), Vector::new(0.5, 0.5, 0.5, 1.0).raw_value());
+/// assert_eq!(vectors[1].raw_value(), Vector::new(1.0, 0.0, 0.0, 0.0).raw_value());
+/// ```
+pub fn apply_gamma_in_place(vectors: &mut [Vector], exponent: f32) {
+    for vector in vectors {
+        *vector = vector.gamma(exponent);
+    }
+}
+
+/// Replaces every vector with its nearest palette entry, as found by `Vector::nearest_xyz`.
+///
+/// Each vector is compared with every palette entry, so this takes time proportional to
+/// `vectors.len() * palette.len()`. Vectors are left unchanged if the palette is empty.
+///
+/// ```
+/// use vec_2_10_10_10::Vector;
+///
+/// let palette = [Vector::new(0.0, 0.0, 0.0, 1.0), Vector::new(1.0, 1.0, 1.0, 1.0)];
+/// let mut vectors = [Vector::new(0.9, 0.8, 0.7, 0.0), Vector::new(0.1, 0.2, 0.3, 0.0)];
+/// vec_2_10_10_10::snap_to_palette_in_place(&mut vectors, &palette);
+///
+/// assert_eq!(vectors[0].raw_value(), palette[1].raw_value());
+/// assert_eq!(vectors[1].raw_value(), palette[0].raw_value());
+/// ```
+pub fn snap_to_palette_in_place(vectors: &mut [Vector], palette: &[Vector]) {
+    for vector in vectors {
+        if let Some(i) = vector.nearest_xyz(palette) {
+            *vector = palette[i];
+        }
+    }
+}
+
+/// Packs every gradient stop `[x, y, z, w]` with `Vector::new`.
+///
+/// ```
+/// let stops = [[0.0, 0.0, 0.0, 1.0], [1.0, 0.5, 0.0, 1.0]];
+/// let packed = vec_2_10_10_10::quantize_gradient(&stops);
+///
+/// assert_eq!(packed.len(), 2);
+/// assert_eq!(packed[1].x(), 1.0);
+/// ```
+pub fn quantize_gradient(stops: &[[f32; 4]]) -> Vec<Vector> {
+    stops.iter().map(|s| Vector::new(s[0], s[1], s[2], s[3])).collect()
+}
+
+/// Estimates how many distinct packed values a linear interpolation between stops would hit.
+///
+/// For every pair of neighbouring stops, the number of quantization levels crossed is
+/// counted per channel, and the channel that crosses most levels determines the number
+/// of bands in that segment. The result is the sum over all segments plus one for the
+/// first stop. A low count for a long gradient means it will visibly band.
+///
+/// This is an estimate: channels change levels at different positions, which can produce
+/// more distinct values, and segments that go back over the same colors produce fewer.
+///
+/// Returns `0` for no stops.
+///
+/// ```
+/// // Black to full red crosses all 1024 levels of x.
+/// assert_eq!(vec_2_10_10_10::gradient_band_count(&[[0.0, 0.0, 0.0, 1.0], [1.0, 0.0, 0.0, 1.0]]), 1024);
+/// // A subtle gradient crosses only a few levels.
+/// assert_eq!(vec_2_10_10_10::gradient_band_count(&[[0.5, 0.5, 0.5, 1.0], [0.505, 0.5, 0.5, 1.0]]), 6);
+/// assert_eq!(vec_2_10_10_10::gradient_band_count(&[]), 0);
+/// ```
+pub fn gradient_band_count(stops: &[[f32; 4]]) -> usize {
+    if stops.is_empty() {
+        return 0;
+    }
+
+    let packed = quantize_gradient(stops);
+    let mut count = 1;
+    for pair in packed.windows(2) {
+        let a = pair[0].to_raw_fields();
+        let b = pair[1].to_raw_fields();
+        let crossed = (0..4)
+            .map(|i| (a[i] as i32 - b[i] as i32).unsigned_abs() as usize)
+            .max()
+            .unwrap_or(0);
+        count += crossed;
+    }
+    count
+}
+
+/// Generates a row-major `width` × `height` buffer with a smooth test gradient.
+///
+/// For column `u` and row `v`, both remapped to `0.0..=1.0`, the vector is
+/// `(u, v, 1.0 - u, (u + v) / 2.0)`: red grows to the right, green grows downwards,
+/// blue fades to the right, and `w` ramps along the diagonal.
+///
+/// ```
+/// let buffer = vec_2_10_10_10::test_gradient(4, 3);
+/// assert_eq!(buffer.len(), 12);
+///
+/// let last = buffer[11];
+/// assert_eq!((last.x(), last.y(), last.z(), last.w()), (1.0, 1.0, 0.0, 1.0));
+///
+/// assert_eq!(buffer[0].z(), 1.0);
+/// assert_eq!(buffer[0].w(), 0.0);
+/// ```
+pub fn test_gradient(width: usize, height: usize) -> Vec<Vector> {
+    let du = (width.max(2) - 1) as f32;
+    let dv = (height.max(2) - 1) as f32;
+
+    let mut buffer = Vec::with_capacity(width * height);
+    for row in 0..height {
+        let v = row as f32 / dv;
+        for col in 0..width {
+            let u = col as f32 / du;
+            buffer.push(Vector::new_clamped(u, v, 1.0 - u, (u + v) / 2.0));
+        }
+    }
+    buffer
+}
+
+/// Samples a gradient of `(position, color)` stops at position `t`, interpolating with `Vector::lerp`.
+///
+/// The stops must be sorted by position. The two stops around `t` are interpolated
+/// in stored values, so this suits data and linear colors. For sRGB-encoded colors use
+/// `sample_gradient_linear`. Positions before the first stop return the first color,
+/// and positions after the last stop return the last color.
+///
+/// Panics if `stops` is empty.
+///
+/// ```
+/// use vec_2_10_10_10::Vector;
+///
+/// let stops = [
+///     (0.0, Vector::new(0.0, 0.0, 0.0, 1.0)),
+///     (0.5, Vector::new(1.0, 0.0, 0.0, 1.0)),
+///     (1.0, Vector::new(1.0, 1.0, 0.0, 1.0)),
+/// ];
+///
+/// let sample = vec_2_10_10_10::sample_gradient(&stops, 0.75);
+/// assert_eq!(sample.x(), 1.0);
+/// assert!(approx_equal(sample.y(), 0.5));
+///
+/// assert_eq!(vec_2_10_10_10::sample_gradient(&stops, -1.0).raw_value(), stops[0].1.raw_value());
+/// assert_eq!(vec_2_10_10_10::sample_gradient(&stops, 2.0).raw_value(), stops[2].1.raw_value());
+/// #
+/// # fn approx_equal(a: f32, b: f32) -> bool {
+/// #     const DELTA: f32 = 0.001;
+/// #     a > b - DELTA && a < b + DELTA
+/// # }
+/// ```
+pub fn sample_gradient(stops: &[(f32, Vector)], t: f32) -> Vector {
+    sample_gradient_with(stops, t, Vector::lerp)
+}
+
+/// Same as `sample_gradient`, but interpolates sRGB-encoded colors in linear space with `Vector::lerp_linear`.
+///
+/// Panics if `stops` is empty.
+pub fn sample_gradient_linear(stops: &[(f32, Vector)], t: f32) -> Vector {
+    sample_gradient_with(stops, t, Vector::lerp_linear)
+}
+
+/// Counts how many times each quantization level is used per channel.
+///
+/// Returns histograms of 1024 levels for `x`, `y` and `z`, and a histogram of 4 levels for `w`.
+///
+/// ```
+/// use vec_2_10_10_10::Vector;
+///
+/// let vectors = [Vector::new(0.0, 1.0, 0.5, 1.0), Vector::new(0.0, 0.0, 0.5, 0.333)];
+/// let (xyz, w) = vec_2_10_10_10::channel_histogram(&vectors);
+///
+/// assert_eq!(xyz[0][0], 2);
+/// assert_eq!(xyz[1][0], 1);
+/// assert_eq!(xyz[1][1023], 1);
+/// assert_eq!(xyz[2][512], 2);
+/// assert_eq!(w, [0, 1, 0, 1]);
+///
+/// // Count of distinct levels used by x.
+/// assert_eq!(xyz[0].iter().filter(|&&c| c > 0).count(), 1);
+/// ```
+pub fn channel_histogram(vectors: &[Vector]) -> ([[u32; 1024]; 3], [u32; 4]) {
+    let mut xyz = [[0u32; 1024]; 3];
+    let mut w = [0u32; 4];
+
+    for vector in vectors {
+        let fields = vector.to_raw_fields();
+        xyz[0][fields[0] as usize] += 1;
+        xyz[1][fields[1] as usize] += 1;
+        xyz[2][fields[2] as usize] += 1;
+        w[fields[3] as usize] += 1;
+    }
+
+    (xyz, w)
+}
+
+/// Returns the number of positions where raw values of two slices differ.
+///
+/// Panics if slice lengths do not match.
+///
+/// ```
+/// use vec_2_10_10_10::Vector;
+///
+/// let a = [Vector::from_raw(1), Vector::from_raw(2), Vector::from_raw(3)];
+/// let b = [Vector::from_raw(1), Vector::from_raw(5), Vector::from_raw(6)];
+///
+/// assert_eq!(vec_2_10_10_10::diff_count(&a, &b), 2);
+/// assert_eq!(vec_2_10_10_10::first_diff(&a, &b), Some(1));
+/// assert_eq!(vec_2_10_10_10::first_diff(&a, &a), None);
+/// ```
+pub fn diff_count(a: &[Vector], b: &[Vector]) -> usize {
+    assert_eq!(a.len(), b.len(), "slice lengths must match");
+    a.iter().zip(b).filter(|&(a, b)| a.raw_value() != b.raw_value()).count()
+}
+
+/// Returns the first position where raw values of two slices differ.
+///
+/// Panics if slice lengths do not match.
+pub fn first_diff(a: &[Vector], b: &[Vector]) -> Option<usize> {
+    assert_eq!(a.len(), b.len(), "slice lengths must match");
+    a.iter().zip(b).position(|(a, b)| a.raw_value() != b.raw_value())
+}
+
+/// Returns the number of vectors with `w` equal to `0.0`.
+///
+/// Only the raw `w` bits are checked, without decoding.
+///
+/// ```
+/// use vec_2_10_10_10::Vector;
+///
+/// let vectors = [Vector::new(1.0, 1.0, 1.0, 0.0), Vector::new(0.0, 0.0, 0.0, 0.333), Vector::TRANSPARENT_BLACK];
+/// assert_eq!(vec_2_10_10_10::count_transparent(&vectors), 2);
+/// ```
+pub fn count_transparent(vectors: &[Vector]) -> usize {
+    vectors.iter().filter(|v| v.raw_value() >> 30 == 0).count()
+}
+
+/// Checks if every vector has `w` equal to `1.0`.
+///
+/// Only the raw `w` bits are checked, without decoding. Returns `true` for an empty slice.
+///
+/// ```
+/// use vec_2_10_10_10::Vector;
+///
+/// assert!(vec_2_10_10_10::all_opaque(&[Vector::OPAQUE_WHITE, Vector::new(0.0, 0.0, 0.0, 1.0)]));
+/// assert!(!vec_2_10_10_10::all_opaque(&[Vector::OPAQUE_WHITE, Vector::new(0.0, 0.0, 0.0, 0.666)]));
+/// ```
+pub fn all_opaque(vectors: &[Vector]) -> bool {
+    vectors.iter().all(|v| v.raw_value() >> 30 == 3)
+}
+
+/// Returns the number of distinct raw values in the slice.
+///
+/// ```
+/// use vec_2_10_10_10::Vector;
+///
+/// let vectors = [Vector::from_raw(1), Vector::from_raw(2), Vector::from_raw(1)];
+/// assert_eq!(vec_2_10_10_10::distinct_count(&vectors), 2);
+/// assert_eq!(vec_2_10_10_10::distinct_count(&[]), 0);
+/// ```
+pub fn distinct_count(vectors: &[Vector]) -> usize {
+    vectors.iter().map(Vector::raw_value).collect::<HashSet<_>>().len()
+}
+
+/// Writes raw data of all vectors as little-endian bytes.
+///
+/// ```
+/// use vec_2_10_10_10::Vector;
+///
+/// let vectors = [Vector::new(0.444, 0.555, 0.666, 0.333), Vector::new(1.0, 0.0, 0.5, 1.0)];
+///
+/// let mut bytes = Vec::new();
+/// vec_2_10_10_10::write_all(&vectors, &mut bytes).unwrap();
+/// assert_eq!(bytes.len(), 8);
+///
+/// let read = vec_2_10_10_10::read_all(&mut &bytes[..]).unwrap();
+/// assert_eq!(read.len(), 2);
+/// assert_eq!(read[0].raw_value(), vectors[0].raw_value());
+/// assert_eq!(read[1].raw_value(), vectors[1].raw_value());
+/// ```
+pub fn write_all<W: io::Write>(vectors: &[Vector], w: &mut W) -> io::Result<()> {
+    for vector in vectors {
+        w.write_all(&vector.raw_value().to_le_bytes())?;
+    }
+    Ok(())
+}
+
+/// Reads vectors from little-endian bytes until the end of reader.
+///
+/// Returns `UnexpectedEof` error if the total number of bytes is not a multiple of 4.
+pub fn read_all<R: io::Read>(r: &mut R) -> io::Result<Vec<Vector>> {
+    let mut bytes = Vec::new();
+    r.read_to_end(&mut bytes)?;
+
+    if bytes.len() % 4 != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            format!("expected a multiple of 4 bytes, got {} bytes", bytes.len())
+        ));
+    }
+
+    Ok(bytes.chunks_exact(4)
+        .map(|c| Vector::from_raw(u32::from_le_bytes([c[0], c[1], c[2], c[3]])))
+        .collect())
+}
+
+/// Computes 64-bit FNV-1a hash of raw values of all vectors.
+///
+/// Raw values are hashed as little-endian bytes, in the same order as written by
+/// `write_all`, so the checksum does not depend on platform or build.
+///
+/// ```
+/// use vec_2_10_10_10::Vector;
+///
+/// assert_eq!(vec_2_10_10_10::checksum(&[]), 0xcbf2_9ce4_8422_2325);
+/// assert_eq!(vec_2_10_10_10::checksum(&[Vector::from_raw(0)]), 0x4d25_767f_9dce_13f5);
+///
+/// let a = [Vector::new(0.2, 0.4, 0.6, 1.0)];
+/// let b = [Vector::new(0.2, 0.4, 0.601, 1.0)];
+/// assert_ne!(vec_2_10_10_10::checksum(&a), vec_2_10_10_10::checksum(&b));
+/// ```
+pub fn checksum(vectors: &[Vector]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let mut hash = OFFSET_BASIS;
+    for vector in vectors {
+        for byte in &vector.raw_value().to_le_bytes() {
+            hash ^= *byte as u64;
+            hash = hash.wrapping_mul(PRIME);
+        }
+    }
+    hash
+}
+
+/// Splits raw values into 4 planes, where plane `i` holds little-endian byte `i` of every value.
+///
+/// Use `from_byte_planes` to join them back.
+///
+/// ```
+/// use vec_2_10_10_10::Vector;
+///
+/// let vectors = [Vector::from_raw(0x4433_2211), Vector::from_raw(0x8877_6655)];
+/// let planes = vec_2_10_10_10::to_byte_planes(&vectors);
+///
+/// assert_eq!(planes[0], [0x11, 0x55]);
+/// assert_eq!(planes[3], [0x44, 0x88]);
+///
+/// let joined = vec_2_10_10_10::from_byte_planes(&planes);
+/// assert_eq!(joined[1].raw_value(), 0x8877_6655);
+/// ```
+pub fn to_byte_planes(vectors: &[Vector]) -> [Vec<u8>; 4] {
+    let mut planes = [
+        Vec::with_capacity(vectors.len()),
+        Vec::with_capacity(vectors.len()),
+        Vec::with_capacity(vectors.len()),
+        Vec::with_capacity(vectors.len()),
+    ];
+    for vector in vectors {
+        for (plane, byte) in planes.iter_mut().zip(&vector.raw_value().to_le_bytes()) {
+            plane.push(*byte);
+        }
+    }
+    planes
+}
+
+/// Joins 4 byte planes returned by `to_byte_planes` back into vectors.
+///
+/// Panics if the planes have different lengths.
+pub fn from_byte_planes(planes: &[Vec<u8>; 4]) -> Vec<Vector> {
+    let len = planes[0].len();
+    assert!(
+        planes.iter().all(|p| p.len() == len),
+        "plane lengths must match: {}, {}, {}, {}",
+        planes[0].len(), planes[1].len(), planes[2].len(), planes[3].len()
+    );
+
+    (0..len)
+        .map(|i| Vector::from_raw(u32::from_le_bytes([planes[0][i], planes[1][i], planes[2][i], planes[3][i]])))
+        .collect()
+}
+
+/// Reinterprets a mutable byte buffer as vectors, without copying.
+///
+/// Every 4 bytes are one raw value in native byte order, so on little-endian platforms
+/// the layout is the same as written by `write_all`.
+///
+/// This is sound because `Vector` is a packed wrapper around `u32`: it has size 4,
+/// alignment 1, and every bit pattern is a valid value.
+///
+/// Panics if the length of the buffer is not a multiple of 4.
+///
+/// ```
+/// use vec_2_10_10_10::Vector;
+///
+/// let mut bytes = vec![0u8; 8];
+/// {
+///     let vectors = vec_2_10_10_10::from_byte_slice_mut(&mut bytes);
+///     assert_eq!(vectors.len(), 2);
+///     vectors[1] = Vector::OPAQUE_WHITE;
+/// }
+/// assert_eq!(bytes, [0, 0, 0, 0, 0xff, 0xff, 0xff, 0xff]);
+/// ```
+pub fn from_byte_slice_mut(bytes: &mut [u8]) -> &mut [Vector] {
+    assert!(
+        bytes.len() % Vector::SIZE == 0,
+        "byte slice length must be a multiple of {}, got {}",
+        Vector::SIZE, bytes.len()
+    );
+
+    // Size and alignment of `Vector` are checked at compile time, next to its definition.
+    unsafe { std::slice::from_raw_parts_mut(bytes.as_mut_ptr() as *mut Vector, bytes.len() / Vector::SIZE) }
+}
+
+/// Returns how many bytes `count` packed vectors save compared to `[f32; 4]`.
+///
+/// Each packed vector takes 4 bytes instead of 16.
+///
+/// ```
+/// assert_eq!(vec_2_10_10_10::bytes_saved_vs_f32x4(0), 0);
+/// assert_eq!(vec_2_10_10_10::bytes_saved_vs_f32x4(1000), 12000);
+/// ```
+pub const fn bytes_saved_vs_f32x4(count: usize) -> usize {
+    count * (mem::size_of::<[f32; 4]>() - Vector::SIZE)
+}
+
+/// Common interface of 4-component vectors packed into 32 bits.
+///
+/// Allows writing code that is generic over the packed format.
+///
+/// ```
+/// use vec_2_10_10_10::{PackedVec4, Vector};
+///
+/// fn brighten<T: PackedVec4>(v: &T) -> T {
+///     let [x, y, z, w] = v.to_array();
+///     T::from_array([x * 2.0, y * 2.0, z * 2.0, w])
+/// }
+///
+/// let value = brighten(&Vector::new(0.25, 0.5, 0.0, 1.0));
+/// assert_eq!(value.raw_value(), Vector::new(0.5, 1.0, 0.0, 1.0).raw_value());
+/// ```
+pub trait PackedVec4: Sized {
+    /// Get `[x, y, z, w]` decoded values.
+    fn to_array(&self) -> [f32; 4];
+
+    /// Creates a new packed vector from `[x, y, z, w]` values, clamping them to the representable range.
+    fn from_array(values: [f32; 4]) -> Self;
+
+    /// Return raw internal value.
+    fn raw_value(&self) -> u32;
+}
+
+impl PackedVec4 for Vector {
+    fn to_array(&self) -> [f32; 4] {
+        self.to_normalized()
+    }
+
+    fn from_array(values: [f32; 4]) -> Vector {
+        Vector::closest_array(&values)
+    }
+
+    fn raw_value(&self) -> u32 {
+        self.data
+    }
+}
+
+impl fmt::Debug for Vector {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set()
+            .entry(&self.x())
+            .entry(&self.y())
+            .entry(&self.z())
+            .entry(&self.w())
+            .finish()
+    }
+}
+
+/// Divides each component by a scalar.
+///
+/// The result is clamped to `0.0..=1.0` like in `Vector::new`.
+///
+/// Dividing by zero saturates: every non-zero component becomes `1.0`, and
+/// zero components stay `0.0`.
+///
+/// ```
+/// let value = vec_2_10_10_10::Vector::new(0.5, 0.25, 0.0, 1.0) / 2.0;
+///
+/// assert!(approx_equal(value.x(), 0.25));
+/// assert!(approx_equal(value.y(), 0.125));
+/// assert!(approx_equal(value.z(), 0.0));
+/// assert!(approx_equal(value.w(), 0.666));
+///
+/// let saturated = vec_2_10_10_10::Vector::new(0.5, 0.25, 0.0, 1.0) / 0.0;
+///
+/// assert!(approx_equal(saturated.x(), 1.0));
+/// assert!(approx_equal(saturated.y(), 1.0));
+/// assert!(approx_equal(saturated.z(), 0.0));
+/// assert!(approx_equal(saturated.w(), 1.0));
+/// #
+/// # fn approx_equal(a: f32, b: f32) -> bool {
+/// #     const DELTA: f32 = 0.001;
+/// #     a > b - DELTA && a < b + DELTA
+/// # }
+/// ```
+impl ops::Div<f32> for Vector {
+    type Output = Vector;
+
+    fn div(self, scalar: f32) -> Vector {
+        if scalar == 0.0 {
+            return Vector::new_clamped(
+                saturate(self.x()),
+                saturate(self.y()),
+                saturate(self.z()),
+                saturate(self.w()),
+            );
+        }
+
+        Vector::new_clamped(
+            self.x() / scalar,
+            self.y() / scalar,
+            self.z() / scalar,
+            self.w() / scalar,
+        )
+    }
+}
+
+/// Multiplies two vectors component-wise ("modulate" blend).
+///
+/// All four components are multiplied, including `w`. Use `Vector::modulate_rgb`
+/// to keep the `w` value of the left side.
+///
+/// ```
+/// use vec_2_10_10_10::Vector;
+///
+/// let value = Vector::new(0.5, 1.0, 0.0, 1.0) * Vector::new(0.5, 0.25, 1.0, 0.333);
+///
+/// assert!(approx_equal(value.x(), 0.25));
+/// assert!(approx_equal(value.y(), 0.25));
+/// assert!(approx_equal(value.z(), 0.0));
+/// assert!(approx_equal(value.w(), 0.333));
+/// #
+/// # fn approx_equal(a: f32, b: f32) -> bool {
+/// #     const DELTA: f32 = 0.001;
+/// #     a > b - DELTA && a < b + DELTA
+/// # }
+/// ```
+impl ops::Mul<Vector> for Vector {
+    type Output = Vector;
+
+    fn mul(self, other: Vector) -> Vector {
+        Vector::new_clamped(
+            self.x() * other.x(),
+            self.y() * other.y(),
+            self.z() * other.z(),
+            self.w() * other.w(),
+        )
+    }
+}
+
+/// Returns the complement `1.0 - c` of all four components, including `w`.
+///
+/// The complement is exact: it simply flips every stored bit.
+///
+/// ```
+/// let value = !vec_2_10_10_10::Vector::new(0.25, 0.0, 1.0, 0.333);
+///
+/// assert!(approx_equal(value.x(), 0.75));
+/// assert!(approx_equal(value.y(), 1.0));
+/// assert!(approx_equal(value.z(), 0.0));
+/// assert!(approx_equal(value.w(), 0.666));
+/// #
+/// # fn approx_equal(a: f32, b: f32) -> bool {
+/// #     const DELTA: f32 = 0.001;
+/// #     a > b - DELTA && a < b + DELTA
+/// # }
+/// ```
+impl ops::Not for Vector {
+    type Output = Vector;
+
+    fn not(self) -> Vector {
+        Vector::from_raw(!self.data)
+    }
+}
+
+/// Formats the vector as `(x, y, z, w)`.
+///
+/// The output can be parsed back with `str::parse`, producing the same vector.
+///
+/// ```
+/// use vec_2_10_10_10::Vector;
+///
+/// let value = Vector::new(0.444, 0.555, 0.666, 0.333);
+/// let parsed: Vector = value.to_string().parse().unwrap();
+///
+/// assert_eq!(Vector::new(1.0, 0.0, 0.0, 1.0).to_string(), "(1, 0, 0, 1)");
+/// assert_eq!(parsed.raw_value(), value.raw_value());
+/// ```
+impl fmt::Display for Vector {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({}, {}, {}, {})", self.x(), self.y(), self.z(), self.w())
+    }
+}
+
+/// Parses a vector from `(x, y, z, w)` format, like the one produced by `Display`.
+///
+/// Whitespace around values and parentheses is ignored. Values are packed with
+/// `Vector::closest`, so they are clamped to `0.0..=1.0`.
+///
+/// ```
+/// use vec_2_10_10_10::{ParseVectorError, Vector};
+///
+/// let value: Vector = " ( 0.444,0.555 , 0.666, 0.333 ) ".parse().unwrap();
+/// assert_eq!(value.raw_value(), Vector::new(0.444, 0.555, 0.666, 0.333).raw_value());
+///
+/// assert_eq!("0.1, 0.2, 0.3, 0.4".parse::<Vector>().unwrap_err(), ParseVectorError::MissingParentheses);
+/// assert_eq!("(0.1, 0.2, 0.3)".parse::<Vector>().unwrap_err(), ParseVectorError::WrongCount(3));
+/// assert!(match "(0.1, 0.2, x, 0.4)".parse::<Vector>() {
+///     Err(ParseVectorError::InvalidFloat(_)) => true,
+///     _ => false,
+/// });
+/// ```
+impl str::FromStr for Vector {
+    type Err = ParseVectorError;
+
+    fn from_str(s: &str) -> Result<Vector, ParseVectorError> {
+        let s = s.trim();
+        if !s.starts_with('(') || !s.ends_with(')') || s.len() < 2 {
+            return Err(ParseVectorError::MissingParentheses);
+        }
+
+        let parts: Vec<&str> = s[1..s.len() - 1].split(',').collect();
+        if parts.len() != 4 {
+            return Err(ParseVectorError::WrongCount(parts.len()));
+        }
+
+        let mut values = [0f32; 4];
+        for (value, part) in values.iter_mut().zip(parts) {
+            *value = part.trim().parse().map_err(ParseVectorError::InvalidFloat)?;
+        }
+
+        Ok(Vector::closest_array(&values))
+    }
+}
+
+/// Error returned when parsing a `Vector` from a string fails.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseVectorError {
+    /// The input is not enclosed in `(` and `)`.
+    MissingParentheses,
+    /// The input does not contain exactly 4 comma-separated values. Contains the number of values found.
+    WrongCount(usize),
+    /// One of the values is not a valid float.
+    InvalidFloat(num::ParseFloatError),
+}
+
+impl fmt::Display for ParseVectorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ParseVectorError::MissingParentheses => write!(f, "vector must be enclosed in parentheses"),
+            ParseVectorError::WrongCount(count) => write!(f, "expected 4 vector values, got {}", count),
+            ParseVectorError::InvalidFloat(ref e) => write!(f, "invalid vector value: {}", e),
+        }
+    }
+}
+
+impl error::Error for ParseVectorError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            ParseVectorError::InvalidFloat(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Packs `cgmath` vector with `Vector::closest`, clamping values to `0.0..=1.0`.
+///
+/// Requires `cgmath` feature.
+///
+/// ```
+/// extern crate cgmath;
+/// # extern crate vec_2_10_10_10;
+///
+/// use vec_2_10_10_10::Vector;
+///
+/// # fn main() {
+/// let value = Vector::from(cgmath::Vector4::new(1.0, 0.0, 0.5, 2.0));
+/// assert_eq!(value.raw_value(), Vector::new(1.0, 0.0, 0.5, 1.0).raw_value());
+///
+/// let back: cgmath::Vector4<f32> = value.into();
+/// assert_eq!(back, cgmath::Vector4::new(value.x(), value.y(), value.z(), value.w()));
+/// # }
+/// ```
+#[cfg(feature = "cgmath")]
+impl From<cgmath::Vector4<f32>> for Vector {
+    fn from(v: cgmath::Vector4<f32>) -> Vector {
+        Vector::closest(v.x, v.y, v.z, v.w)
+    }
+}
+
+/// Decodes the vector into `cgmath` vector.
+///
+/// Requires `cgmath` feature.
+#[cfg(feature = "cgmath")]
+impl From<Vector> for cgmath::Vector4<f32> {
+    fn from(v: Vector) -> cgmath::Vector4<f32> {
+        cgmath::Vector4::new(v.x(), v.y(), v.z(), v.w())
+    }
+}
+
+/// Creates a vector from exactly 4 bytes of little-endian raw data.
+///
+/// Pairs well with `chunks_exact(4)` when parsing byte buffers.
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use vec_2_10_10_10::Vector;
+///
+/// let value = Vector::new(0.444, 0.555, 0.666, 0.333);
+/// let bytes = value.raw_value().to_le_bytes();
+///
+/// let parsed = Vector::try_from(&bytes[..]).unwrap();
+/// assert_eq!(parsed.raw_value(), value.raw_value());
+///
+/// let error = Vector::try_from(&bytes[..3]).unwrap_err();
+/// assert_eq!(error.slice_len(), 3);
+/// ```
+impl<'a> TryFrom<&'a [u8]> for Vector {
+    type Error = InvalidLengthError;
+
+    fn try_from(bytes: &'a [u8]) -> Result<Vector, InvalidLengthError> {
+        if bytes.len() != 4 {
+            return Err(InvalidLengthError {
+                len: bytes.len()
+            });
+        }
+
+        Ok(Vector::from_raw(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])))
+    }
+}
+
+/// Error returned when a byte slice of the wrong length is converted to a `Vector`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct InvalidLengthError {
+    len: usize,
+}
+
+impl InvalidLengthError {
+    /// Length of the slice that was passed in.
+    pub fn slice_len(&self) -> usize {
+        self.len
+    }
+}
+
+impl fmt::Display for InvalidLengthError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "expected a slice of 4 bytes, got {} bytes", self.len)
+    }
+}
+
+impl error::Error for InvalidLengthError {}
+
+/// Wrapper that formats a `Vector` as a table of raw fields and decoded values with `{:?}`.
+///
+/// ```
+/// use vec_2_10_10_10::{DebugFields, Vector};
+///
+/// let value = Vector::from_raw_fields([512, 0, 1023, 2]);
+/// assert_eq!(
+///     format!("{:?}", DebugFields(value)),
+///     "x:  512/1023  0.5005\n\
+///      y:    0/1023  0.0000\n\
+///      z: 1023/1023  1.0000\n\
+///      w:    2/3     0.6667"
+/// );
+/// ```
+#[derive(Copy, Clone)]
+pub struct DebugFields(pub Vector);
+
+impl fmt::Debug for DebugFields {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let fields = self.0.to_raw_fields();
+        let values = self.0.to_normalized();
+        for (i, name) in ["x", "y", "z", "w"].iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            let max = if i < 3 { "1023" } else { "3   " };
+            write!(f, "{}: {:>4}/{}  {:.4}", name, fields[i], max, values[i])?;
+        }
+        Ok(())
+    }
+}
+
+#[inline]
+const fn quantize_to(value: f32, max: u32) -> u32 {
+    let scaled = clamp(value) * max as f32;
+    // Rounds half away from zero like `f32::round`, which is not `const` before Rust 1.90.
+    // Adding `0.5` before truncating would round `0.49999997` up, because the sum is `1.0` in `f32`.
+    let q = scaled as u32;
+    let q = if scaled - q as f32 >= 0.5 { q + 1 } else { q };
+    // `max` is not exact in `f32` above 24 bits and may round up to `2^bits`.
+    if q > max { max } else { q }
+}
+
+#[inline]
+const fn clamp(c: f32) -> f32 {
+    if c < 0.0 {
+        return 0.0;
+    }
+    if c > 1.0 {
+        return 1.0;
+    }
+    c
+}
+
+#[inline]
+fn in_range(c: f32) -> bool {
+    const EPSILON: f32 = 0.001;
+    (-EPSILON..=1.0 + EPSILON).contains(&c)
+}
+
+#[inline]
+fn saturate(c: f32) -> f32 {
+    if c > 0.0 {
+        return 1.0;
+    }
+    0.0
+}
+
+/// Applies sRGB transfer function (OETF) to a linear `0.0..=1.0` value.
+fn srgb_encode(c: f32) -> f32 {
+    if c <= 0.003_130_8 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Converts an sRGB-encoded `0.0..=1.0` value to linear (EOTF).
+fn srgb_decode(c: f32) -> f32 {
+    if c <= 0.040_45 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Converts sRGB color to CIELAB `[L, a, b]` with D65 white point.
+fn srgb_to_lab(r: f32, g: f32, b: f32) -> [f32; 3] {
+    let (r, g, b) = (srgb_decode(r), srgb_decode(g), srgb_decode(b));
+
+    // Linear sRGB to XYZ, normalized by D65 white.
+    let x = (0.412_456_4 * r + 0.357_576_1 * g + 0.180_437_5 * b) / 0.950_47;
+    let y = 0.212_672_9 * r + 0.715_152_2 * g + 0.072_175 * b;
+    let z = (0.019_333_9 * r + 0.119_192 * g + 0.950_304_1 * b) / 1.088_83;
+
+    fn f(t: f32) -> f32 {
+        const DELTA: f32 = 6.0 / 29.0;
+        if t > DELTA * DELTA * DELTA {
+            t.cbrt()
+        } else {
+            t / (3.0 * DELTA * DELTA) + 4.0 / 29.0
+        }
+    }
+
+    let (fx, fy, fz) = (f(x), f(y), f(z));
+    [116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz)]
+}
+
+/// Converts RGB to hue in degrees `0.0..360.0`, saturation and value.
+fn rgb_to_hsv(r: f32, g: f32, b: f32) -> (f32, f32, f32) {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+
+    let h = if delta == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    let s = if max == 0.0 { 0.0 } else { delta / max };
+
+    (h, s, max)
+}
+
+/// Converts hue in degrees `0.0..360.0`, saturation and value to RGB.
+fn hsv_to_rgb(h: f32, s: f32, v: f32) -> (f32, f32, f32) {
+    let c = v * s;
+    let h = h / 60.0;
+    let x = c * (1.0 - (h.rem_euclid(2.0) - 1.0).abs());
+    let m = v - c;
+
+    let (r, g, b) = match h as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+
+    (r + m, g + m, b + m)
+}
+
+fn sample_gradient_with(stops: &[(f32, Vector)], t: f32, lerp: fn(&Vector, &Vector, f32) -> Vector) -> Vector {
+    assert!(!stops.is_empty(), "gradient must have at least one stop");
+
+    let next = stops.iter().position(|&(position, _)| position > t);
+    match next {
+        Some(0) => stops[0].1,
+        None => stops[stops.len() - 1].1,
+        Some(i) => {
+            let (p0, c0) = stops[i - 1];
+            let (p1, c1) = stops[i];
+            lerp(&c0, &c1, (t - p0) / (p1 - p0))
+        }
+    }
+}